@@ -0,0 +1,526 @@
+use anyhow::Context;
+
+use crate::{
+	location::Source,
+	tokenizer::{self, matching_close, Lang, Token, TokenKind},
+};
+
+#[derive(Debug)]
+pub struct ClassDef<'a> {
+	pub name: &'a str,
+	pub superclass: Option<&'a str>,
+	pub comment: Option<&'a str>,
+	pub decls: Vec<Declaration<'a>>,
+}
+
+/// A single member declaration at the top level of a class body.
+#[derive(Debug)]
+pub struct Declaration<'a> {
+	pub comment: Option<&'a str>,
+	/// declaration text up to the terminating `;` or body
+	pub text: &'a str,
+	/// text between the braces of an inline body, if present
+	pub body: Option<&'a str>,
+	pub access: Access,
+	pub section: Section,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+	Public,
+	Protected,
+	Private,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Section {
+	Normal,
+	Signals,
+	Slots,
+}
+
+/// Macros that prefix a declaration instead of standing on their own.
+const PREFIX_MACROS: &[&str] = &[
+	"QSDOC_HIDE",
+	"QSDOC_DEPRECATED",
+	"Q_INVOKABLE",
+	"Q_SIGNAL",
+	"Q_SLOT",
+	"Q_REQUIRED_RESULT",
+];
+
+fn is_statement_macro(ident: &str) -> bool {
+	(ident.starts_with("Q_") || ident.starts_with("QML_") || ident.starts_with("QSDOC_"))
+		&& !ident.starts_with("Q_DECL_")
+		&& !PREFIX_MACROS.contains(&ident)
+		&& ident
+			.chars()
+			.all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// Finds every class or struct definition in a header, including nested ones.
+pub fn find_classes<'a>(src: Source<'a>) -> anyhow::Result<Vec<ClassDef<'a>>> {
+	let text = src.text;
+	let tokens = tokenizer::tokenize(src, Lang::Cpp)?;
+	let mut classes = Vec::new();
+	let mut comment = CommentTracker::default();
+
+	for i in 0..tokens.len() {
+		let token = &tokens[i];
+
+		match token.kind {
+			TokenKind::DocComment => {
+				comment.push(token);
+				continue
+			},
+			TokenKind::Comment => {
+				comment.clear();
+				continue
+			},
+			_ => {},
+		}
+
+		let this_comment = comment.take(text);
+
+		if !(token.is_ident("class") || token.is_ident("struct")) {
+			continue
+		}
+
+		// `enum class`, `template <class T>`, `friend class Foo;`
+		if i > 0
+			&& (tokens[i - 1].is_ident("enum")
+				|| tokens[i - 1].is_punct("<")
+				|| tokens[i - 1].is_punct(","))
+		{
+			continue
+		}
+
+		let Some(class) = parse_class(src, &tokens, i, this_comment)
+			.with_context(|| format!("while parsing class at {}", src.at(token.start)))?
+		else {
+			continue
+		};
+
+		classes.push(class);
+	}
+
+	Ok(classes)
+}
+
+fn parse_class<'a>(
+	src: Source<'a>,
+	tokens: &[Token<'a>],
+	keyword: usize,
+	comment: Option<&'a str>,
+) -> anyhow::Result<Option<ClassDef<'a>>> {
+	let default_access = match tokens[keyword].text {
+		"struct" => Access::Public,
+		_ => Access::Private,
+	};
+
+	let mut name = None;
+	let mut i = keyword + 1;
+
+	// name, skipping export macros and attributes
+	loop {
+		let Some(token) = tokens.get(i) else { return Ok(None) };
+
+		match token.kind {
+			TokenKind::Ident if token.text == "final" => {},
+			TokenKind::Ident => name = Some(token.text),
+			TokenKind::Punct if token.text == "[" || token.text == "(" => {
+				i = matching_close(src, tokens, i).context("unbalanced brackets")?;
+			},
+			TokenKind::Punct if token.text == ":" || token.text == "{" => break,
+			// forward declarations, variable declarations and the like
+			_ => return Ok(None),
+		}
+
+		i += 1;
+	}
+
+	let Some(name) = name else { return Ok(None) };
+
+	let mut superclass = None;
+
+	if tokens[i].is_punct(":") {
+		i += 1;
+
+		let mut first = true;
+		let mut access = default_access;
+		let mut template_depth = 0usize;
+
+		loop {
+			let Some(token) = tokens.get(i) else { return Ok(None) };
+
+			match token.text {
+				"{" if template_depth == 0 => break,
+				";" => return Ok(None),
+				"<" => template_depth += 1,
+				">" => template_depth = template_depth.saturating_sub(1),
+				"," if template_depth == 0 => first = false,
+				"public" => access = Access::Public,
+				"protected" => access = Access::Protected,
+				"private" => access = Access::Private,
+				"virtual" => {},
+				_ if first
+					&& template_depth == 0
+					&& access == Access::Public
+					&& token.kind == TokenKind::Ident =>
+				{
+					// qualified names are stored by their last segment, like class names are
+					superclass = Some(token.text);
+				},
+				_ => {},
+			}
+
+			i += 1;
+		}
+	}
+
+	let open = i;
+	let close = matching_close(src, tokens, open).context("unterminated class body")?;
+
+	let decls = parse_body(src, &tokens[open + 1..close], default_access)
+		.with_context(|| format!("while parsing class `{name}`"))?;
+
+	Ok(Some(ClassDef {
+		name,
+		superclass,
+		comment,
+		decls,
+	}))
+}
+
+fn parse_body<'a>(
+	src: Source<'a>,
+	tokens: &[Token<'a>],
+	default_access: Access,
+) -> anyhow::Result<Vec<Declaration<'a>>> {
+	let text = src.text;
+	let mut decls = Vec::new();
+	let mut access = default_access;
+	let mut section = Section::Normal;
+	let mut comment = CommentTracker::default();
+	let mut i = 0;
+
+	let next_is = |i: usize, s: &str| tokens.get(i).map(|t| t.is_punct(s)).unwrap_or(false);
+
+	while i < tokens.len() {
+		let token = &tokens[i];
+
+		match token.kind {
+			TokenKind::DocComment => {
+				comment.push(token);
+				i += 1;
+				continue
+			},
+			TokenKind::Comment => {
+				comment.clear();
+				i += 1;
+				continue
+			},
+			_ => {},
+		}
+
+		// access specifiers, e.g. `public:`, `public slots:`, `Q_SIGNALS:`
+		let specifier = match token.text {
+			"public" => Some(Access::Public),
+			"protected" => Some(Access::Protected),
+			"private" => Some(Access::Private),
+			_ => None,
+		};
+
+		if let Some(specifier) = specifier {
+			if next_is(i + 1, ":") {
+				access = specifier;
+				section = Section::Normal;
+				comment.clear();
+				i += 2;
+				continue
+			}
+
+			if matches!(tokens.get(i + 1).map(|t| t.text), Some("slots" | "Q_SLOTS"))
+				&& next_is(i + 2, ":")
+			{
+				access = specifier;
+				section = Section::Slots;
+				comment.clear();
+				i += 3;
+				continue
+			}
+		}
+
+		if matches!(token.text, "signals" | "Q_SIGNALS") && next_is(i + 1, ":") {
+			access = Access::Public;
+			section = Section::Signals;
+			comment.clear();
+			i += 2;
+			continue
+		}
+
+		if token.is_punct(";") {
+			comment.clear();
+			i += 1;
+			continue
+		}
+
+		let decl_comment = comment.take(text);
+		let start = i;
+
+		// statement macros such as `Q_OBJECT` or `Q_PROPERTY(...)` may omit the semicolon
+		let mut macro_ = i;
+		loop {
+			if tokens[macro_].is_ident("QSDOC_HIDE") {
+				macro_ += 1;
+			} else if tokens[macro_].is_ident("QSDOC_DEPRECATED") {
+				// optionally followed by the replacement, e.g. `QSDOC_DEPRECATED(Foo.bar())`
+				macro_ = match next_is(macro_ + 1, "(") {
+					true => matching_close(src, tokens, macro_ + 1)
+						.context("unbalanced parens in QSDOC_DEPRECATED")?,
+					false => macro_,
+				} + 1;
+			} else {
+				break
+			}
+
+			if macro_ >= tokens.len() {
+				macro_ = i;
+				break
+			}
+		}
+
+		if let Some(token) = tokens
+			.get(macro_)
+			.filter(|t| t.kind == TokenKind::Ident && is_statement_macro(t.text))
+		{
+			let mut end = macro_;
+
+			if next_is(macro_ + 1, "(") {
+				end = matching_close(src, tokens, macro_ + 1).with_context(|| {
+					format!("unbalanced parens in macro at {}", src.at(token.start))
+				})?;
+			}
+
+			decls.push(Declaration {
+				comment: decl_comment,
+				text: &text[tokens[start].start..tokens[end].end()],
+				body: None,
+				access,
+				section,
+			});
+
+			i = end + 1;
+			continue
+		}
+
+		// everything else runs until a `;` or the end of a body at depth 0
+		let mut body = None;
+		let mut end = None;
+		let mut init_list = false;
+		let is_type_decl = matches!(
+			tokens[start].text,
+			"class" | "struct" | "union" | "enum" | "typedef" | "using"
+		);
+
+		while i < tokens.len() {
+			let token = &tokens[i];
+
+			if token.kind != TokenKind::Punct {
+				i += 1;
+				continue
+			}
+
+			match token.text {
+				";" => {
+					end = Some(i);
+					i += 1;
+					break
+				},
+				":" if i > 0 && tokens[i - 1].is_punct(")") => {
+					init_list = true;
+					i += 1;
+				},
+				// member initializers in a constructor initializer list, e.g. `a{1}`
+				"{" if init_list
+					&& i > 0 && (tokens[i - 1].kind == TokenKind::Ident
+					|| tokens[i - 1].is_punct(">")) =>
+				{
+					i = matching_close(src, tokens, i).context("unbalanced braces")? + 1;
+				},
+				"{" => {
+					let close = matching_close(src, tokens, i).context("unbalanced braces")?;
+
+					end = Some(i);
+					body = Some(match close > i + 1 {
+						true => &text[tokens[i].end()..tokens[close].start],
+						false => "",
+					});
+
+					i = close + 1;
+
+					// `struct X { ... } x;` and `enum X { ... };`
+					if is_type_decl || next_is(i, ";") {
+						while i < tokens.len() && !tokens[i].is_punct(";") {
+							i += 1;
+						}
+
+						i += 1;
+					}
+
+					break
+				},
+				"(" | "[" => {
+					i = matching_close(src, tokens, i).context("unbalanced brackets")? + 1;
+				},
+				_ => i += 1,
+			}
+		}
+
+		let end = end.unwrap_or(tokens.len());
+
+		if end > start {
+			decls.push(Declaration {
+				comment: decl_comment,
+				text: &text[tokens[start].start..tokens[end - 1].end()],
+				body,
+				access,
+				section,
+			});
+		}
+	}
+
+	Ok(decls)
+}
+
+/// Tracks the span of `///` comments preceding the next declaration.
+#[derive(Default)]
+struct CommentTracker {
+	span: Option<(usize, usize)>,
+}
+
+impl CommentTracker {
+	fn push(&mut self, token: &Token) {
+		self.span = Some(match self.span {
+			Some((start, _)) => (start, token.end()),
+			None => (token.start, token.end()),
+		});
+	}
+
+	fn clear(&mut self) {
+		self.span = None;
+	}
+
+	fn take<'a>(&mut self, text: &'a str) -> Option<&'a str> {
+		self.span.take().map(|(start, end)| &text[start..end])
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn classes(text: &str) -> Vec<ClassDef<'_>> {
+		find_classes(Source::new("test.hpp", text)).unwrap()
+	}
+
+	#[test]
+	fn nested_classes() {
+		let classes = classes(
+			"class Outer: public QObject {
+				Q_OBJECT;
+			public:
+				/// inner
+				class Inner: public Base {
+					void inner();
+				};
+
+				void outer();
+			};",
+		);
+
+		let names = classes.iter().map(|c| c.name).collect::<Vec<_>>();
+		assert_eq!(names, ["Outer", "Inner"]);
+		assert_eq!(classes[1].superclass, Some("Base"));
+		assert_eq!(classes[1].comment, Some("/// inner"));
+
+		// the nested class body does not leak into the outer class
+		let outer = classes[0].decls.iter().map(|d| d.text).collect::<Vec<_>>();
+		assert_eq!(outer, [
+			"Q_OBJECT",
+			"class Inner: public Base",
+			"void outer()"
+		]);
+		assert_eq!(classes[1].decls[0].text, "void inner()");
+	}
+
+	#[test]
+	fn bodies_and_defaults() {
+		let classes = classes(
+			"class Foo {
+			public:
+				int get() const { return [this]() { return this->x; }(); }
+				void set(QList<int> list = {1, 2}, Qt::Flags flags = {});
+				std::function<void()> callback = [] {};
+			};",
+		);
+
+		let decls = &classes[0].decls;
+		assert_eq!(decls.len(), 3);
+		assert_eq!(decls[0].text, "int get() const");
+		assert!(decls[0].body.unwrap().contains("this->x"));
+		assert_eq!(
+			decls[1].text,
+			"void set(QList<int> list = {1, 2}, Qt::Flags flags = {})"
+		);
+		assert_eq!(decls[1].body, None);
+		assert!(decls[2].text.starts_with("std::function<void()> callback"));
+	}
+
+	#[test]
+	fn access_and_sections() {
+		let classes = classes(
+			"class Foo: public QObject {
+				Q_OBJECT;
+				void privateFn();
+			public:
+				void publicFn();
+			signals:
+				void changed();
+			public slots:
+				void slot();
+			protected:
+				Q_SIGNAL void marked();
+			};",
+		);
+
+		let decls = classes[0]
+			.decls
+			.iter()
+			.map(|d| (d.text, d.access, d.section))
+			.collect::<Vec<_>>();
+
+		assert_eq!(decls, [
+			("Q_OBJECT", Access::Private, Section::Normal),
+			("void privateFn()", Access::Private, Section::Normal),
+			("void publicFn()", Access::Public, Section::Normal),
+			("void changed()", Access::Public, Section::Signals),
+			("void slot()", Access::Public, Section::Slots),
+			("Q_SIGNAL void marked()", Access::Protected, Section::Normal),
+		]);
+	}
+
+	#[test]
+	fn structs_default_public() {
+		let classes = classes("struct Foo { Q_GADGET; int x; };");
+		assert!(classes[0].decls.iter().all(|d| d.access == Access::Public));
+	}
+
+	#[test]
+	fn unbalanced_brackets() {
+		let src = Source::new("test.hpp", "class Foo {\n\tvoid f(;\n};");
+		let err = format!("{:#}", find_classes(src).unwrap_err());
+
+		assert!(err.ends_with("unterminated `{` at test.hpp:1:11"), "{err}");
+	}
+}
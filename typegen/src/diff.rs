@@ -0,0 +1,563 @@
+use std::{
+	collections::{BTreeMap, BTreeSet, HashMap},
+	fmt::{self, Write},
+};
+
+use serde::Serialize;
+
+use crate::{
+	resolver,
+	typespec::{Class, Enum, FnParam, Function, QmlTypeMapping, TypeSpec},
+};
+
+/// A type that was added, removed or changed between two typespec snapshots.
+#[derive(Debug, Serialize)]
+pub struct TypeDiff {
+	pub module: String,
+	pub name: String,
+	pub kind: TypeKind,
+	pub change: Change,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub members: Vec<MemberDiff>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MemberDiff {
+	pub kind: MemberKind,
+	pub name: String,
+	pub change: Change,
+	/// signature before the change, if the member existed
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub old: Option<String>,
+	/// signature after the change, if the member still exists
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub new: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TypeKind {
+	Class,
+	Enum,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MemberKind {
+	Property,
+	Function,
+	Signal,
+	Enum,
+	Variant,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Change {
+	Added,
+	Removed,
+	Changed,
+}
+
+impl fmt::Display for MemberKind {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			Self::Property => "property",
+			Self::Function => "function",
+			Self::Signal => "signal",
+			Self::Enum => "enum",
+			Self::Variant => "variant",
+		})
+	}
+}
+
+/// Signatures of every member of a type, grouped by kind and name so overloads stay together.
+pub type Members = BTreeMap<(MemberKind, String), BTreeSet<String>>;
+
+/// Every type of a typespec, keyed by module and qml name.
+pub type Api = BTreeMap<(String, String), TypeEntry>;
+
+pub struct TypeEntry {
+	pub kind: TypeKind,
+	pub members: Members,
+	/// signature of each function by its name and declared parameter types, e.g.
+	/// `foo(int,QString)`, see `resolver::declared_signature`
+	pub overloads: BTreeMap<String, String>,
+}
+
+/// Compares the QML api described by two typespecs.
+pub fn diff(old: &TypeSpec, new: &TypeSpec) -> Vec<TypeDiff> {
+	let old_types = collect_types(old);
+	let mut new_types = collect_types(new);
+	let mut diffs = Vec::new();
+
+	for ((module, name), old_type) in old_types {
+		let Some(new_type) = new_types.remove(&(module.clone(), name.clone())) else {
+			diffs.push(TypeDiff {
+				module,
+				name,
+				kind: old_type.kind,
+				change: Change::Removed,
+				members: Vec::new(),
+			});
+			continue
+		};
+
+		let members = diff_members(old_type.members, new_type.members);
+
+		if !members.is_empty() {
+			diffs.push(TypeDiff {
+				module,
+				name,
+				kind: new_type.kind,
+				change: Change::Changed,
+				members,
+			});
+		}
+	}
+
+	for ((module, name), new_type) in new_types {
+		diffs.push(TypeDiff {
+			module,
+			name,
+			kind: new_type.kind,
+			change: Change::Added,
+			members: Vec::new(),
+		});
+	}
+
+	diffs.sort_by(|a, b| Ord::cmp(&(&a.module, &a.name), &(&b.module, &b.name)));
+	diffs
+}
+
+fn diff_members(old: Members, mut new: Members) -> Vec<MemberDiff> {
+	let mut diffs = Vec::new();
+
+	let member =
+		|kind, name: &str, change, old: Option<&String>, new: Option<&String>| MemberDiff {
+			kind,
+			name: name.to_string(),
+			change,
+			old: old.cloned(),
+			new: new.cloned(),
+		};
+
+	for ((kind, name), old_sigs) in old {
+		let new_sigs = new.remove(&(kind, name.clone())).unwrap_or_default();
+
+		if old_sigs == new_sigs {
+			continue
+		}
+
+		// a single signature changing in place, otherwise overloads are matched exactly
+		if old_sigs.len() == 1 && new_sigs.len() == 1 {
+			let (old, new) = (old_sigs.first(), new_sigs.first());
+			diffs.push(member(kind, &name, Change::Changed, old, new));
+			continue
+		}
+
+		for sig in old_sigs.difference(&new_sigs) {
+			diffs.push(member(kind, &name, Change::Removed, Some(sig), None));
+		}
+
+		for sig in new_sigs.difference(&old_sigs) {
+			diffs.push(member(kind, &name, Change::Added, None, Some(sig)));
+		}
+	}
+
+	for ((kind, name), new_sigs) in new {
+		for sig in &new_sigs {
+			diffs.push(member(kind, &name, Change::Added, None, Some(sig)));
+		}
+	}
+
+	diffs
+}
+
+/// Lookup tables over a typespec, like `resolver::Index`.
+struct Index<'a> {
+	/// named qml types by c++ name
+	qml_types: HashMap<&'a str, Vec<&'a QmlTypeMapping>>,
+	/// classes by c++ name, the first one winning as with a linear search
+	classes: HashMap<&'a str, &'a Class>,
+}
+
+impl<'a> Index<'a> {
+	fn new(typespec: &'a TypeSpec) -> Self {
+		let mut qml_types = HashMap::<_, Vec<_>>::new();
+		for type_ in typespec.typemap.iter().filter(|t| !t.anonymous) {
+			qml_types.entry(&type_.cname[..]).or_default().push(type_);
+		}
+
+		let mut classes = HashMap::new();
+		for class in &typespec.classes {
+			classes.entry(&class.name[..]).or_insert(class);
+		}
+
+		Self { qml_types, classes }
+	}
+}
+
+/// Collects the members of every qml visible type defined by a typespec.
+pub fn collect_types(typespec: &TypeSpec) -> Api {
+	let index = Index::new(typespec);
+	let mut types = BTreeMap::new();
+
+	for class in &typespec.classes {
+		// only types visible from qml are part of the api, hidden superclasses are flattened into
+		// their subclasses below
+		let Some(name) = index
+			.qml_types
+			.get(&class.name[..])
+			.and_then(|types| {
+				types
+					.iter()
+					.find(|t| t.module.as_ref() == Some(&class.module))
+			})
+			.map(|t| t.name.clone())
+		else {
+			continue
+		};
+
+		let mut entry = TypeEntry {
+			kind: TypeKind::Class,
+			members: Members::new(),
+			overloads: BTreeMap::new(),
+		};
+
+		let find = |name: &String| index.classes.get(&name[..]).copied();
+
+		add_members(&mut entry, class, "");
+
+		// members of QML_EXTENDED extensions are documented on the extended class
+		if let Some(extension) = class.extension.as_ref().and_then(find) {
+			add_members(&mut entry, extension, "");
+			inherit_members(&index, &mut entry, &extension.superclass, "");
+		}
+
+		inherit_members(&index, &mut entry, &class.superclass, "");
+
+		// attached members are used as `Type.member`, and named that way here
+		if let Some(attached) = class.attached.as_ref().and_then(find) {
+			let prefix = format!("{name}.");
+			add_members(&mut entry, attached, &prefix);
+			inherit_members(&index, &mut entry, &attached.superclass, &prefix);
+		}
+
+		types.insert((class.module.clone(), name), entry);
+	}
+
+	for enum_ in &typespec.enums {
+		let Some(module) = &enum_.module else { continue };
+		let mut members = Members::new();
+		add_variants(&mut members, None, enum_);
+
+		types.insert((module.clone(), enum_.name.clone()), TypeEntry {
+			kind: TypeKind::Enum,
+			members,
+			overloads: BTreeMap::new(),
+		});
+	}
+
+	types
+}
+
+/// Adds the members of superclasses not visible from qml, which are documented on their subclasses.
+fn inherit_members<'a>(
+	index: &Index<'a>,
+	entry: &mut TypeEntry,
+	mut superclass: &'a String,
+	prefix: &str,
+) {
+	while !index.qml_types.contains_key(&superclass[..]) {
+		let Some(class) = index.classes.get(&superclass[..]) else { break };
+
+		add_members(entry, class, prefix);
+		superclass = &class.superclass;
+	}
+}
+
+/// Adds the members of `class` with names prefixed by `prefix`, skipping members already added
+/// by nearer classes, which override them. Functions are overridden per overload, so inherited
+/// overloads with other parameters are still added.
+fn add_members(entry: &mut TypeEntry, class: &Class, prefix: &str) {
+	for (key, sigs) in class_members(class, prefix) {
+		entry.members.entry(key).or_insert(sigs);
+	}
+
+	for func in &class.functions {
+		let id = format!(
+			"{prefix}{}({})",
+			func.name,
+			resolver::declared_signature(&func.params)
+		);
+
+		if entry.overloads.contains_key(&id) {
+			continue
+		}
+
+		let sig = function_signature(func, prefix);
+		entry
+			.members
+			.entry((MemberKind::Function, format!("{prefix}{}", func.name)))
+			.or_default()
+			.insert(sig.clone());
+		entry.overloads.insert(id, sig);
+	}
+}
+
+fn class_members(class: &Class, prefix: &str) -> Members {
+	let mut members = Members::new();
+
+	let mut add = |kind, name: &str, sig: String| {
+		members
+			.entry((kind, format!("{prefix}{name}")))
+			.or_default()
+			.insert(sig);
+	};
+
+	for prop in &class.properties {
+		let mut sig = format!("{prefix}{}: {}", prop.name, display_type(&prop.type_));
+
+		let mut flags = Vec::new();
+
+		if prop.default {
+			flags.push("default");
+		}
+
+		if !prop.readable {
+			flags.push("writeonly");
+		} else if !prop.writable {
+			flags.push("readonly");
+		}
+
+		if !flags.is_empty() {
+			write!(sig, " [{}]", flags.join(", ")).unwrap();
+		}
+
+		add(MemberKind::Property, &prop.name, sig);
+	}
+
+	// functions are added per overload by `add_members`
+	for signal in &class.signals {
+		let sig = format!(
+			"{prefix}{}({})",
+			signal.name,
+			display_params(&signal.params)
+		);
+		add(MemberKind::Signal, &signal.name, sig);
+	}
+
+	for enum_ in &class.enums {
+		members
+			.entry((MemberKind::Enum, enum_.name.clone()))
+			.or_default()
+			.insert(enum_.name.clone());
+
+		add_variants(&mut members, Some(&enum_.name), enum_);
+	}
+
+	members
+}
+
+fn add_variants(members: &mut Members, prefix: Option<&str>, enum_: &Enum) {
+	for variant in &enum_.varaints {
+		let name = match prefix {
+			Some(prefix) => format!("{prefix}.{}", variant.name),
+			None => variant.name.clone(),
+		};
+
+		members
+			.entry((MemberKind::Variant, name.clone()))
+			.or_default()
+			.insert(name);
+	}
+}
+
+fn function_signature(func: &Function, prefix: &str) -> String {
+	format!(
+		"{prefix}{}({}): {}",
+		func.name,
+		display_params(&func.params),
+		display_type(&func.ret)
+	)
+}
+
+fn display_type(type_: &str) -> &str {
+	type_.strip_prefix("QML:").unwrap_or(type_)
+}
+
+fn display_params(params: &[FnParam]) -> String {
+	params
+		.iter()
+		.map(|p| match &p.default {
+			Some(default) => format!("{}: {} = {default}", p.name, display_type(&p.type_)),
+			None => format!("{}: {}", p.name, display_type(&p.type_)),
+		})
+		.collect::<Vec<_>>()
+		.join(", ")
+}
+
+/// Formats a diff as markdown release notes, grouped by module.
+pub fn to_markdown(diffs: &[TypeDiff]) -> String {
+	let mut md = String::from("# API changes\n");
+
+	if diffs.is_empty() {
+		md += "\nNo changes.\n";
+		return md
+	}
+
+	let mut modules = BTreeMap::<&str, Vec<&TypeDiff>>::new();
+	for diff in diffs {
+		modules.entry(&diff.module).or_default().push(diff);
+	}
+
+	for (module, diffs) in modules {
+		write!(md, "\n## {module}\n").unwrap();
+
+		for (change, title) in [
+			(Change::Added, "Added types"),
+			(Change::Removed, "Removed types"),
+		] {
+			let types = diffs
+				.iter()
+				.filter(|d| d.change == change)
+				.collect::<Vec<_>>();
+
+			if !types.is_empty() {
+				write!(md, "\n### {title}\n\n").unwrap();
+
+				for type_ in types {
+					let kind = match type_.kind {
+						TypeKind::Class => "",
+						TypeKind::Enum => " (enum)",
+					};
+
+					writeln!(md, "- `{}`{kind}", type_.name).unwrap();
+				}
+			}
+		}
+
+		let changed = diffs
+			.iter()
+			.filter(|d| d.change == Change::Changed)
+			.collect::<Vec<_>>();
+
+		if !changed.is_empty() {
+			md += "\n### Changed types\n";
+
+			for type_ in changed {
+				write!(md, "\n#### `{}`\n\n", type_.name).unwrap();
+
+				for member in &type_.members {
+					match member.change {
+						Change::Added => writeln!(
+							md,
+							"- Added {} `{}`",
+							member.kind,
+							member.new.as_ref().unwrap()
+						),
+						Change::Removed => writeln!(
+							md,
+							"- Removed {} `{}`",
+							member.kind,
+							member.old.as_ref().unwrap()
+						),
+						Change::Changed => writeln!(
+							md,
+							"- Changed {} `{}` to `{}`",
+							member.kind,
+							member.old.as_ref().unwrap(),
+							member.new.as_ref().unwrap()
+						),
+					}
+					.unwrap();
+				}
+			}
+		}
+	}
+
+	md
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::testutil;
+
+	/// A type deriving from a superclass hidden from qml, which declares `base`'s members.
+	fn typespec(thing: Class, base: Class) -> TypeSpec {
+		let mut typespec = testutil::typespec(vec![thing, base]);
+		typespec.typemap.retain(|t| t.name != "Base");
+		typespec
+	}
+
+	#[test]
+	fn overridden_members() {
+		let mut thing = testutil::class("Thing", "Base");
+		let mut foo = testutil::property("foo", "qint32");
+		foo.writable = false;
+		let mut f = testutil::function("f", &["qint32"]);
+		f.ret = "QString".to_string();
+		thing.properties = vec![foo];
+		thing.functions = vec![f];
+
+		let mut base = testutil::class("Base", "QObject");
+		base.properties = vec![testutil::property("foo", "QString")];
+		base.functions = vec![
+			testutil::function("f", &["qint32"]),
+			testutil::function("f", &["QString"]),
+		];
+
+		let api = collect_types(&typespec(thing, base));
+		let thing = &api[&(testutil::MODULE.to_string(), "Thing".to_string())];
+
+		let members = thing
+			.members
+			.iter()
+			.map(|((_, name), sigs)| (&name[..], sigs.iter().map(|s| &s[..]).collect()))
+			.collect::<Vec<(_, Vec<_>)>>();
+
+		// inherited overloads with other parameters are kept
+		assert_eq!(members, [
+			("foo", vec!["foo: qint32 [readonly]"]),
+			("f", vec!["f(p0: QString): void", "f(p0: qint32): QString"]),
+		]);
+
+		assert_eq!(thing.overloads["f(qint32)"], "f(p0: qint32): QString");
+	}
+
+	#[test]
+	fn overridden_changes() {
+		let thing = || {
+			let mut thing = testutil::class("Thing", "Base");
+			thing.properties = vec![testutil::property("foo", "qint32")];
+			thing
+		};
+
+		let base = |type_| {
+			let mut base = testutil::class("Base", "QObject");
+			base.properties = vec![testutil::property("foo", type_)];
+			base.functions = vec![testutil::function("g", &[type_])];
+			base
+		};
+
+		let diffs = diff(
+			&typespec(thing(), base("QString")),
+			&typespec(thing(), base("qint32")),
+		);
+
+		let changes = diffs[0]
+			.members
+			.iter()
+			.map(|m| (m.change, m.old.as_deref(), m.new.as_deref()))
+			.collect::<Vec<_>>();
+
+		// changes to the overridden `foo` are not part of the api
+		assert_eq!(changes, [(
+			Change::Changed,
+			Some("g(p0: QString): void"),
+			Some("g(p0: qint32): void")
+		)]);
+	}
+}
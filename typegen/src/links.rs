@@ -0,0 +1,389 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use fancy_regex::Regex;
+
+use crate::outform::{self, SourceLocation, TypeDetails, TypeInfo};
+
+/// Anchors available on a documented type page, as targeted by `@@` links.
+#[derive(Default)]
+struct Members {
+	props: HashSet<String>,
+	funcs: HashSet<String>,
+	/// anchors of functions, e.g. `foo`, `foo(2)` or `foo(int,string)`
+	func_ids: HashSet<String>,
+	/// number of overloads matching each selector, e.g. `foo(2)` or `foo(int,string)`
+	overloads: HashMap<String, usize>,
+	signals: HashSet<String>,
+}
+
+/// A `@@` link whose target does not exist.
+#[derive(Debug)]
+pub struct DanglingLink {
+	/// the link as it was written, e.g. `@@Quickshell.Foo.bar()`
+	pub link: String,
+	pub reason: String,
+	pub module: String,
+	/// qml name of the type whose documentation contains the link
+	pub class: String,
+	/// documentation containing the link, e.g. "property `foo`"
+	pub member: String,
+	pub source: Option<SourceLocation>,
+}
+
+/// Index of every locally documented type, used to validate links between them.
+pub struct LinkIndex {
+	link_regex: Regex,
+	types: HashMap<(String, String), Members>,
+}
+
+impl LinkIndex {
+	pub fn new() -> Self {
+		Self {
+			// see reformat::TypeLinks for the format
+			link_regex: Regex::new(r#"TYPE(99M(?<src>QS|QT)_(?<module>\w+?)99N(?<name>\w+?))?(99V(?<mname>\w+?)99T(?<mtype>prop|func|signal)(99O(?<overload>.*?))?)?99TYPE"#).unwrap(),
+			types: HashMap::new(),
+		}
+	}
+
+	pub fn add(&mut self, types: &BTreeMap<String, TypeInfo>) {
+		for type_ in types.values() {
+			let mut members = Members::default();
+
+			if let TypeDetails::Class(class) = &type_.details {
+				members
+					.props
+					.extend(class.properties.iter().map(|p| p.name.clone()));
+				members
+					.funcs
+					.extend(class.functions.iter().map(|f| f.name.clone()));
+
+				for func in &class.functions {
+					members.func_ids.insert(func.id.clone());
+					members.func_ids.extend(func.arity_id.clone());
+					members.func_ids.extend(func.name_id.clone());
+
+					if !func.overloads.is_empty() {
+						let arity = format!("{}({})", func.name, func.params.len());
+
+						for selector in [func.id.clone(), arity] {
+							*members.overloads.entry(selector).or_default() += 1;
+						}
+					}
+				}
+				members
+					.signals
+					.extend(class.signals.iter().map(|s| s.name.clone()));
+			}
+
+			self.types
+				.insert((type_.module.clone(), type_.name.clone()), members);
+		}
+	}
+
+	/// Checks every link in the documentation of `types` against the indexed types.
+	///
+	/// Links to Qt types and to modules that were not indexed cannot be checked and are skipped.
+	pub fn check(&self, types: &BTreeMap<String, TypeInfo>) -> Vec<DanglingLink> {
+		let indexed_modules = self
+			.types
+			.keys()
+			.map(|(module, _)| module as &str)
+			.collect::<HashSet<_>>();
+
+		let mut dangling = Vec::new();
+
+		for type_ in types.values() {
+			let mut check = |text: &Option<String>,
+			                 member: &str,
+			                 source: &Option<SourceLocation>| {
+				let Some(text) = text else { return };
+
+				for captures in self.link_regex.captures_iter(text).flatten() {
+					let get = |name: &str| captures.name(name).map(|m| m.as_str());
+
+					let (module, name) = match get("src") {
+						Some("QT") => continue,
+						Some(_) => (
+							get("module").unwrap().replace('_', "."),
+							get("name").unwrap(),
+						),
+						// links without a type refer to the type being documented
+						None => (type_.module.clone(), &type_.name as &str),
+					};
+
+					if !indexed_modules.contains(&module as &str) {
+						continue
+					}
+
+					let (mtype, mname) = (get("mtype"), get("mname").unwrap_or(""));
+					let overload = get("overload");
+
+					let link = match mtype {
+						Some("func") => {
+							format!("@@{module}.{name}.{mname}({})", overload.unwrap_or(""))
+						},
+						Some("signal") => format!("@@{module}.{name}.{mname}(s)"),
+						Some(_) => format!("@@{module}.{name}.{mname}"),
+						None => format!("@@{module}.{name}"),
+					};
+
+					let reason = match self.types.get(&(module.clone(), name.to_string())) {
+						None => Some(format!("no type `{name}` in module `{module}`")),
+						Some(members) => match mtype {
+							Some("prop") if !members.props.contains(mname) => {
+								Some(format!("`{name}` has no property `{mname}`"))
+							},
+							Some("func") if !members.funcs.contains(mname) => {
+								Some(format!("`{name}` has no function `{mname}`"))
+							},
+							Some("func") if overload.is_some() => {
+								let selector = format!("{mname}({})", overload.unwrap());
+
+								match members.overloads.get(&selector) {
+									_ if members.func_ids.contains(&selector) => None,
+									None => Some(format!("`{name}` has no overload `{selector}`")),
+									Some(_) => Some(format!(
+											"`{selector}` matches multiple overloads, select one by parameter types"
+										)),
+								}
+							},
+							Some("signal") if !members.signals.contains(mname) => {
+								Some(format!("`{name}` has no signal `{mname}`"))
+							},
+							_ => None,
+						},
+					};
+
+					if let Some(reason) = reason {
+						dangling.push(DanglingLink {
+							link,
+							reason,
+							module: type_.module.clone(),
+							class: type_.name.clone(),
+							member: member.to_string(),
+							source: source.clone(),
+						});
+					}
+				}
+			};
+
+			match &type_.details {
+				TypeDetails::Class(class) => {
+					check(&class.description, "description", &class.source);
+					check(&class.details, "details", &class.source);
+					check(&class.replacement, "deprecation", &class.source);
+
+					for prop in &class.properties {
+						let member = format!("property `{}`", prop.name);
+						check(&prop.details, &member, &prop.source);
+						check(&prop.replacement, &member, &prop.source);
+					}
+
+					for func in &class.functions {
+						let member = format!("function `{}`", func.name);
+						check(&func.details, &member, &func.source);
+						check(&func.replacement, &member, &func.source);
+					}
+
+					for signal in &class.signals {
+						let member = format!("signal `{}`", signal.name);
+						check(&signal.details, &member, &signal.source);
+						check(&signal.replacement, &member, &signal.source);
+					}
+
+					check_variants(&mut check, &class.variants, &class.source);
+
+					if let Some(attached) = &class.attached {
+						for prop in &attached.properties {
+							let member = format!("attached property `{}`", prop.name);
+							check(&prop.details, &member, &prop.source);
+							check(&prop.replacement, &member, &prop.source);
+						}
+
+						for func in &attached.functions {
+							let member = format!("attached function `{}`", func.name);
+							check(&func.details, &member, &func.source);
+							check(&func.replacement, &member, &func.source);
+						}
+
+						for signal in &attached.signals {
+							let member = format!("attached signal `{}`", signal.name);
+							check(&signal.details, &member, &signal.source);
+							check(&signal.replacement, &member, &signal.source);
+						}
+					}
+				},
+				TypeDetails::Enum(enum_) => {
+					check(&enum_.description, "description", &enum_.source);
+					check(&enum_.details, "details", &enum_.source);
+					check(&enum_.replacement, "deprecation", &enum_.source);
+					check_variants(&mut check, &enum_.variants, &enum_.source);
+				},
+			}
+		}
+
+		dangling
+	}
+}
+
+fn check_variants(
+	check: &mut impl FnMut(&Option<String>, &str, &Option<SourceLocation>),
+	variants: &BTreeMap<String, outform::Variant>,
+	source: &Option<SourceLocation>,
+) {
+	for (name, variant) in variants {
+		let member = format!("variant `{name}`");
+		check(&variant.details, &member, source);
+		check(&variant.replacement, &member, source);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		parse::MemberOrder,
+		reformat::{Context, ReformatPass, TypeLinks},
+		resolver,
+		testutil,
+	};
+
+	/// Rewrites `@@` links into placeholders as done for the site.
+	fn reformat(links: &str) -> String {
+		let mut text = links.to_string();
+		TypeLinks.reformat(
+			&Context {
+				module: testutil::MODULE,
+			},
+			&mut text,
+		);
+		text
+	}
+
+	/// Resolves a type with overloaded functions, described by `description`.
+	fn resolve(description: &str) -> BTreeMap<String, TypeInfo> {
+		let mut thing = testutil::class("Thing", "QObject");
+		thing.description = Some(reformat(description));
+		thing.functions = vec![
+			testutil::function("f", &["qint32"]),
+			testutil::function("f", &["QString"]),
+			testutil::function("f", &["QList<qint32>"]),
+			testutil::function("f", &["QString", "qint32"]),
+			testutil::function("g", &["qint32"]),
+		];
+
+		let typespec = testutil::typespec(vec![thing]);
+		resolver::resolve_types(testutil::MODULE, &[], typespec, MemberOrder::Source)
+			.unwrap()
+			.types
+	}
+
+	/// Links in the description of a type with overloaded functions that do not resolve,
+	/// as `(link, reason)`.
+	fn dangling(links: &str) -> Vec<(String, String)> {
+		let types = resolve(links);
+
+		let mut index = LinkIndex::new();
+		index.add(&types);
+
+		index
+			.check(&types)
+			.into_iter()
+			.map(|d| (d.link, d.reason))
+			.collect()
+	}
+
+	#[test]
+	fn overload_selectors() {
+		let links = "@@Thing.f() @@Thing.f(2) @@Thing.f(int) @@Thing.f(string, int) @@g()";
+		assert_eq!(dangling(links), []);
+	}
+
+	#[test]
+	fn dangling_overloads() {
+		let dangling = dangling("@@f(1) @@f(3) @@f(qint32) @@g(int) @@Thing.missing()");
+		let links = dangling
+			.iter()
+			.map(|(link, _)| &link[..])
+			.collect::<Vec<_>>();
+
+		assert_eq!(links, [
+			"@@Quickshell.Thing.f(1)",
+			"@@Quickshell.Thing.f(3)",
+			"@@Quickshell.Thing.f(qint32)",
+			"@@Quickshell.Thing.g(int)",
+			"@@Quickshell.Thing.missing()",
+		]);
+
+		assert!(dangling[0].1.contains("matches multiple overloads"));
+		assert_eq!(dangling[1].1, "`Thing` has no overload `f(3)`");
+		// overloads are selected by qml type names
+		assert_eq!(dangling[2].1, "`Thing` has no overload `f(qint32)`");
+		assert_eq!(dangling[4].1, "`Thing` has no function `missing`");
+	}
+
+	#[test]
+	fn overload_anchors() {
+		let types = resolve("");
+		let TypeDetails::Class(thing) = &types["Thing"].details else { unreachable!() };
+
+		let anchors = thing
+			.functions
+			.iter()
+			.flat_map(|f| [Some(&f.id), f.arity_id.as_ref(), f.name_id.as_ref()])
+			.flatten()
+			.map(|id| format!("func.{id}"))
+			.collect::<HashSet<_>>();
+
+		let regex = LinkIndex::new().link_regex;
+
+		// every placeholder segment is passed to `layouts/shortcodes/qmltypelink.html`, and
+		// `layouts/partials/qmltype.html` links functions to `func.<mname>(<overload>)`
+		for link in [
+			"@@Thing.f(2)",
+			"@@Thing.f(int)",
+			"@@Thing.f(list<int>)",
+			"@@Thing.f(string, int)",
+			"@@Thing.g()",
+		] {
+			let placeholder = reformat(link);
+			let captures = regex.captures(&placeholder).unwrap().unwrap();
+			let mname = &captures["mname"];
+
+			let anchor = match captures.name("overload") {
+				Some(overload) => format!("func.{mname}({})", overload.as_str()),
+				None => format!("func.{mname}"),
+			};
+
+			assert!(
+				anchors.contains(&anchor),
+				"{link}: no anchor `{anchor}` in {anchors:?}"
+			);
+		}
+	}
+
+	#[test]
+	fn dangling_members_and_types() {
+		let links = "@@Thing.missing @@Thing.f @@Missing @@Quickshell.Missing.foo @@Qt.Missing";
+
+		// links to modules that were not indexed cannot be checked
+		assert_eq!(dangling(links), [
+			(
+				"@@Quickshell.Thing.missing".to_string(),
+				"`Thing` has no property `missing`".to_string()
+			),
+			(
+				"@@Quickshell.Thing.f".to_string(),
+				"`Thing` has no property `f`".to_string()
+			),
+			(
+				"@@Quickshell.Missing".to_string(),
+				"no type `Missing` in module `Quickshell`".to_string()
+			),
+			(
+				"@@Quickshell.Missing.foo".to_string(),
+				"no type `Missing` in module `Quickshell`".to_string()
+			),
+		]);
+	}
+}
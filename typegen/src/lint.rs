@@ -0,0 +1,230 @@
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::{
+	location::Location,
+	parse::{self, ClassType, ParseContext},
+};
+
+/// Documentation coverage of a single module.
+#[derive(Debug, Serialize)]
+pub struct ModuleCoverage {
+	pub module: String,
+	pub documented: usize,
+	pub total: usize,
+	/// percentage of documented entities
+	pub coverage: f64,
+	pub missing: Vec<Missing>,
+}
+
+/// An entity lacking documentation.
+#[derive(Debug, Serialize)]
+pub struct Missing {
+	pub kind: Kind,
+	pub name: String,
+	pub file: String,
+	pub line: usize,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Kind {
+	Class,
+	/// class comment without a `!` short description
+	Description,
+	Property,
+	Function,
+	Signal,
+	Enum,
+	Variant,
+}
+
+impl fmt::Display for Missing {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self.kind {
+			Kind::Class => write!(f, "class `{}` is undocumented", self.name),
+			Kind::Description => write!(f, "class `{}` has no short description", self.name),
+			Kind::Property => write!(f, "property `{}` is undocumented", self.name),
+			Kind::Function => write!(f, "function `{}` is undocumented", self.name),
+			Kind::Signal => write!(f, "signal `{}` is undocumented", self.name),
+			Kind::Enum => write!(f, "enum `{}` is undocumented", self.name),
+			Kind::Variant => write!(f, "variant `{}` is undocumented", self.name),
+		}
+	}
+}
+
+struct Linter {
+	documented: usize,
+	total: usize,
+	missing: Vec<Missing>,
+}
+
+impl Linter {
+	fn check(&mut self, documented: bool, kind: Kind, name: String, location: Location) {
+		self.total += 1;
+
+		match documented {
+			true => self.documented += 1,
+			false => self.missing.push(Missing {
+				kind,
+				name,
+				file: location.file.to_string(),
+				line: location.line,
+			}),
+		}
+	}
+
+	fn check_enum(&mut self, name: String, enum_: &parse::EnumInfo) {
+		self.check(
+			enum_.comment.is_some(),
+			Kind::Enum,
+			name.clone(),
+			enum_.location,
+		);
+
+		for variant in &enum_.variants {
+			self.check(
+				variant.comment.is_some(),
+				Kind::Variant,
+				format!("{name}.{}", variant.name),
+				variant.location,
+			);
+		}
+	}
+}
+
+/// Finds every class, member, enum and variant of a module lacking a `///` comment.
+pub fn lint(ctx: &ParseContext) -> ModuleCoverage {
+	let mut linter = Linter {
+		documented: 0,
+		total: 0,
+		missing: Vec::new(),
+	};
+
+	// gadget documentation is not rendered, only their property types are,
+	// and foreign registrations document the type they register
+	for class in ctx
+		.classes
+		.iter()
+		.filter(|class| matches!(class.type_, ClassType::Object) && class.foreign.is_none())
+	{
+		let name = class.qml_name.as_deref().unwrap_or(&class.name);
+
+		// a class counts once, missing either its comment or the short description in it
+		let (documented, kind) = match class.comment {
+			None => (false, Kind::Class),
+			Some(comment) => (
+				parse::parse_details_desc(comment).0.is_some(),
+				Kind::Description,
+			),
+		};
+
+		linter.check(documented, kind, name.to_string(), class.location);
+
+		for prop in &class.properties {
+			linter.check(
+				prop.comment.is_some(),
+				Kind::Property,
+				format!("{name}.{}", prop.name),
+				prop.location,
+			);
+		}
+
+		for func in &class.invokables {
+			linter.check(
+				func.comment.is_some(),
+				Kind::Function,
+				format!("{name}.{}", func.name),
+				func.location,
+			);
+		}
+
+		for signal in &class.signals {
+			linter.check(
+				signal.comment.is_some(),
+				Kind::Signal,
+				format!("{name}.{}", signal.name),
+				signal.location,
+			);
+		}
+
+		for enum_ in &class.enums {
+			linter.check_enum(format!("{name}.{}", enum_.qml_name), enum_);
+		}
+	}
+
+	for enum_ in &ctx.enums {
+		linter.check_enum(enum_.qml_name.to_string(), enum_);
+	}
+
+	// namespace enums are checked after every class, so put them back in source order
+	linter
+		.missing
+		.sort_by(|a, b| Ord::cmp(&(&a.file, a.line), &(&b.file, b.line)));
+
+	let coverage = match linter.total {
+		0 => 100.0,
+		total => linter.documented as f64 * 100.0 / total as f64,
+	};
+
+	ModuleCoverage {
+		module: ctx.module.to_string(),
+		documented: linter.documented,
+		total: linter.total,
+		coverage,
+		missing: linter.missing,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::parse::CppParser;
+
+	#[test]
+	fn class_coverage() {
+		let mut ctx = ParseContext::new("Test");
+		CppParser::new()
+			.parse(
+				"test.hpp",
+				"class Undocumented: public QObject {
+					Q_OBJECT;
+					QML_ELEMENT;
+				};
+
+				/// Details without a short description.
+				class Detailed: public QObject {
+					Q_OBJECT;
+					QML_ELEMENT;
+				};
+
+				///! Short description.
+				class Documented: public QObject {
+					Q_OBJECT;
+					QML_ELEMENT;
+				public:
+					/// Documented function.
+					Q_INVOKABLE void f();
+					Q_INVOKABLE void g();
+				};",
+				&mut ctx,
+			)
+			.unwrap();
+
+		let coverage = lint(&ctx);
+		let missing = coverage
+			.missing
+			.iter()
+			.map(ToString::to_string)
+			.collect::<Vec<_>>();
+
+		assert_eq!(missing, [
+			"class `Undocumented` is undocumented",
+			"class `Detailed` has no short description",
+			"function `Documented.g` is undocumented",
+		]);
+
+		assert_eq!((coverage.documented, coverage.total), (2, 5));
+	}
+}
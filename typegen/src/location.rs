@@ -0,0 +1,51 @@
+use std::fmt;
+
+/// A file being parsed, used to locate slices of its text.
+#[derive(Debug, Clone, Copy)]
+pub struct Source<'a> {
+	pub file: &'a str,
+	pub text: &'a str,
+}
+
+impl<'a> Source<'a> {
+	pub fn new(file: &'a str, text: &'a str) -> Self {
+		Self { file, text }
+	}
+
+	/// Locates the byte offset `offset` in this source's text.
+	pub fn at(&self, offset: usize) -> Location<'a> {
+		let before = &self.text[..offset];
+		let line = before.matches('\n').count() + 1;
+		let col = before.len() - before.rfind('\n').map(|i| i + 1).unwrap_or(0) + 1;
+
+		Location {
+			file: self.file,
+			line,
+			col,
+		}
+	}
+
+	/// Locates `slice`, which must be borrowed from this source's text.
+	pub fn locate(&self, slice: &str) -> Location<'a> {
+		let start = slice.as_ptr() as usize - self.text.as_ptr() as usize;
+		assert!(
+			start + slice.len() <= self.text.len(),
+			"slice is not part of the source text"
+		);
+		self.at(start)
+	}
+}
+
+/// A position within a parsed file.
+#[derive(Debug, Clone, Copy)]
+pub struct Location<'a> {
+	pub file: &'a str,
+	pub line: usize,
+	pub col: usize,
+}
+
+impl fmt::Display for Location<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}:{}:{}", self.file, self.line, self.col)
+	}
+}
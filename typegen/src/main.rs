@@ -1,19 +1,48 @@
-use std::{
-	collections::HashMap,
-	path::{Path, PathBuf},
-};
+use std::path::Path;
 
-use anyhow::{anyhow, Context};
+use anyhow::{anyhow, bail, Context};
 use walkdir::WalkDir;
 
+mod cpp;
+mod diff;
+mod links;
+mod lint;
+mod location;
 mod outform;
 mod parse;
+mod qml;
+mod qmltypes;
 mod reformat;
 mod resolver;
+mod since;
+#[cfg(test)]
+mod testutil;
+mod tokenizer;
 mod typespec;
 
 fn main() -> anyhow::Result<()> {
-	let args = std::env::args().collect::<Vec<_>>();
+	let mut args = std::env::args().collect::<Vec<_>>();
+
+	// fail instead of warning when documented members reference unknown types
+	let deny_unknown = args.iter().any(|arg| arg == "--deny-unknown");
+	args.retain(|arg| arg != "--deny-unknown");
+
+	// `--history <version>=<typespecs>` for each past release, oldest first
+	let mut history = Vec::new();
+	while let Some(i) = args.iter().position(|arg| arg == "--history") {
+		let value = args
+			.get(i + 1)
+			.expect("expected <version>=<typespecs> after --history");
+		let (version, path) = value.split_once('=').with_context(|| {
+			format!("invalid history `{value}`, expected <version>=<typespecs>")
+		})?;
+
+		let typespec = load_typespecs(&typespec_files(path)?)?;
+		history.push((version.to_string(), typespec));
+		args.drain(i..i + 2);
+	}
+
+	let history = (!history.is_empty()).then(|| since::History::new(&history));
 
 	match args.get(1).map(|v| v as &str) {
 		Some("fulltypegen") => {
@@ -41,13 +70,15 @@ fn main() -> anyhow::Result<()> {
 				})
 				.collect::<Result<Vec<(String, parse::ModuleInfoHeader)>, anyhow::Error>>()?;
 
+			let srcroot = path;
+
 			println!("Generating types -> {outpath}");
 
 			for (path, header) in module_files.iter() {
 				let name = &header.name;
 				let mod_outpath = format!("{outpath}/{name}.json");
 				println!("Gentypes :: {path} ({name}) -> {mod_outpath}");
-				gentypes(path, &mod_outpath)?;
+				gentypes(path, &mod_outpath, Some(srcroot))?;
 			}
 
 			let mut typefiledirs = extratypedirs.to_vec();
@@ -55,40 +86,94 @@ fn main() -> anyhow::Result<()> {
 
 			println!("Generating docs {typefiledirs:?} -> {datapath}");
 
-			// this is crap but I don't care, typegen is getting replaced
+			// sorted per directory so type resolution doesn't depend on directory order
 			let typefiles = typefiledirs
 				.iter()
-				.map(|dir| {
-					let dirs = Path::new(dir)
-						.read_dir()?
-						.flatten()
-						.map(|e| e.path().to_string_lossy().to_string())
-						.collect::<Vec<String>>();
-					Ok::<_, anyhow::Error>(dirs)
-				})
-				.collect::<Result<Vec<Vec<String>>, anyhow::Error>>()?
+				.map(|dir| typespec_files(dir))
+				.collect::<anyhow::Result<Vec<_>>>()?
 				.into_iter()
 				.flatten()
 				.collect::<Vec<String>>();
 
+			let mut resolved = Vec::new();
+
 			for (path, header) in module_files.iter() {
 				let name = &header.name;
 				let mod_datapath = format!("{datapath}/{name}");
 				let mod_templatepath = format!("{templatepath}/{name}");
 				println!("Gendocs :: {path} ({name}) to {mod_datapath}");
-				gendocs(path, &mod_datapath, &mod_templatepath, &typefiles[..])?;
+				resolved.push(gendocs(
+					path,
+					&mod_datapath,
+					&mod_templatepath,
+					&typefiles[..],
+					history.as_ref(),
+				)?);
 			}
+
+			report(&resolved, deny_unknown)?;
 		},
 		Some("gentypes") => {
 			let modinfo = args.get(2).expect("expected module file");
 			let outpath = args.get(3).expect("expected output path");
-			gentypes(modinfo, outpath)?;
+			let srcroot = args.get(4).map(|v| v as &str);
+			gentypes(modinfo, outpath, srcroot)?;
 		},
 		Some("gendocs") => {
 			let modinfo = args.get(2).expect("expected module file");
 			let datapath = args.get(3).expect("expected datapath");
 			let templatepath = args.get(4).expect("expected templatepath");
-			gendocs(modinfo, datapath, templatepath, &args[5..])?;
+			let resolved = gendocs(
+				modinfo,
+				datapath,
+				templatepath,
+				&args[5..],
+				history.as_ref(),
+			)?;
+			report(&[resolved], deny_unknown)?;
+		},
+		Some("qmltypes") => {
+			let modinfo = args.get(2).expect("expected module file");
+			let outpath = args.get(3).expect("expected output path");
+			genqmltypes(modinfo, outpath, &args[4..])?;
+		},
+		Some("lint") => {
+			let mut json = false;
+			let mut threshold = None;
+			let mut modinfos = Vec::new();
+			let mut rest = args[2..].iter();
+
+			while let Some(arg) = rest.next() {
+				match &arg[..] {
+					"--json" => json = true,
+					"--threshold" => {
+						let value = rest.next().expect("expected threshold percentage");
+						threshold = Some(
+							value
+								.parse::<f64>()
+								.with_context(|| format!("invalid threshold `{value}`"))?,
+						);
+					},
+					_ => modinfos.push(arg),
+				}
+			}
+
+			if modinfos.is_empty() {
+				panic!("expected module files");
+			}
+
+			lint(&modinfos, json, threshold)?;
+		},
+		Some("diff") => {
+			let json = args.iter().any(|arg| arg == "--json");
+			let paths = args[2..]
+				.iter()
+				.filter(|arg| *arg != "--json")
+				.collect::<Vec<_>>();
+
+			let old = paths.first().expect("expected old typespec directory");
+			let new = paths.get(1).expect("expected new typespec directory");
+			gendiff(old, new, json)?;
 		},
 		_ => {
 			panic!("typegen invoked without mode");
@@ -98,12 +183,33 @@ fn main() -> anyhow::Result<()> {
 	Ok(())
 }
 
-fn gentypes(modinfo: &str, outpath: &str) -> anyhow::Result<()> {
+fn gentypes(modinfo: &str, outpath: &str, srcroot: Option<&str>) -> anyhow::Result<()> {
+	parse_sources(modinfo, srcroot, |module, ctx| {
+		let typespec = ctx.gen_typespec(&module.header.name);
+
+		let text = serde_json::to_string_pretty(&typespec).unwrap();
+
+		std::fs::write(outpath, text).context("saving typespec")
+	})
+}
+
+/// Parses every header and qml file of a module, then hands the result to `f`.
+fn parse_sources<R>(
+	modinfo: &str,
+	srcroot: Option<&str>,
+	f: impl FnOnce(&parse::ModuleInfo, &parse::ParseContext) -> anyhow::Result<R>,
+) -> anyhow::Result<R> {
 	let path = Path::new(modinfo);
 	let dir = path.parent().unwrap();
 	let text = std::fs::read_to_string(path).expect("failed to read module file");
 	let module = parse::parse_module(&text)?;
 
+	// source locations are recorded relative to the source root when one is given
+	let srcdir = srcroot
+		.and_then(|root| dir.strip_prefix(root).ok())
+		.unwrap_or(Path::new(""));
+	let srcpath = |file: &str| srcdir.join(file).to_string_lossy().to_string();
+
 	let texts = module
 		.header
 		.headers
@@ -116,9 +222,9 @@ fn gentypes(modinfo: &str, outpath: &str) -> anyhow::Result<()> {
 				)
 			})?;
 
-			Ok::<_, anyhow::Error>((header, text))
+			Ok::<_, anyhow::Error>((srcpath(header), text))
 		})
-		.collect::<Result<HashMap<_, _>, _>>()?;
+		.collect::<Result<Vec<_>, _>>()?;
 
 	let qml_texts = module
 		.header
@@ -132,37 +238,32 @@ fn gentypes(modinfo: &str, outpath: &str) -> anyhow::Result<()> {
 				)
 			})?;
 
-			Ok::<_, anyhow::Error>((file, text))
+			Ok::<_, anyhow::Error>((srcpath(file), text))
 		})
-		.collect::<Result<HashMap<_, _>, _>>()?;
+		.collect::<Result<Vec<_>, _>>()?;
 
 	let header_parser = parse::CppParser::new();
 	let qml_parser = parse::QmlParser::new();
 	let mut ctx = parse::ParseContext::new(&module.header.name);
+	ctx.nullable_pointers = module.header.nullable_pointers;
+
+	texts.iter().try_for_each(|(header, text)| {
+		header_parser
+			.parse(header, text, &mut ctx)
+			.with_context(|| format!("while parsing module header `{header}`"))
+	})?;
+
+	qml_texts.iter().try_for_each(|(file, text)| {
+		qml_parser
+			.parse(file, text, &mut ctx)
+			.with_context(|| format!("while parsing module qml file `{file}`"))
+	})?;
+
+	for warning in &ctx.warnings {
+		eprintln!("warning: {warning}");
+	}
 
-	texts
-		.iter()
-		.map(|(header, text)| {
-			header_parser
-				.parse(&text, &mut ctx)
-				.with_context(|| format!("while parsing module header `{header}`"))
-		})
-		.collect::<Result<_, _>>()?;
-
-	qml_texts
-		.iter()
-		.map(|(file, text)| {
-			qml_parser
-				.parse(&file, &text, &mut ctx)
-				.with_context(|| format!("while parsing module qml file `{file}`"))
-		})
-		.collect::<Result<_, _>>()?;
-
-	let typespec = ctx.gen_typespec(&module.header.name);
-
-	let text = serde_json::to_string_pretty(&typespec).unwrap();
-
-	std::fs::write(outpath, text).context("saving typespec")
+	f(&module, &ctx)
 }
 
 fn gendocs(
@@ -170,34 +271,31 @@ fn gendocs(
 	datapath: &str,
 	templatepath: &str,
 	typepaths: &[String],
-) -> anyhow::Result<()> {
+	history: Option<&since::History>,
+) -> anyhow::Result<resolver::Resolved> {
 	let text = std::fs::read_to_string(modinfo).expect("failed to read module file");
 	let module = parse::parse_module(&text)?;
-
-	let mut typespec = typespec::TypeSpec::default();
-
-	for path in typepaths {
-		let text =
-			std::fs::read_to_string(&path).with_context(|| anyhow!("attempting to read {path}"))?;
-
-		let ts = serde_json::from_str::<typespec::TypeSpec>(&text)
-			.with_context(|| anyhow!("attempting to parse {path}"))?;
-
-		typespec.typemap.extend(ts.typemap);
-		typespec.classes.extend(ts.classes);
-		typespec.gadgets.extend(ts.gadgets);
-		typespec.enums.extend(ts.enums);
+	let typespec = load_typespecs(typepaths)?;
+
+	let api = history.map(|_| diff::collect_types(&typespec));
+	let mut resolved = resolver::resolve_types(
+		&module.header.name,
+		&module.header.imports,
+		typespec,
+		module.header.member_order,
+	)?;
+
+	if let (Some(history), Some(api)) = (history, api) {
+		history.annotate(&api, &mut resolved.types);
 	}
 
-	let types = resolver::resolve_types(&module.header.name, typespec)?;
-
 	let datapath = Path::new(datapath);
 	let templatepath = Path::new(templatepath);
 	std::fs::create_dir_all(datapath)?;
 	std::fs::create_dir_all(templatepath)?;
 
-	for (name, info) in types {
-		let json = serde_json::to_string_pretty(&info).unwrap();
+	for (name, info) in &resolved.types {
+		let json = serde_json::to_string_pretty(info).unwrap();
 		let datapath = datapath.join(format!("{name}.json"));
 		std::fs::write(&datapath, json).with_context(|| format!("while writing {datapath:?}"))?;
 
@@ -222,6 +320,7 @@ hidetitle = true
 		name: module.header.name.to_string(),
 		description: module.header.description,
 		details: module.details.to_string(),
+		deprecated: resolver::deprecated_api(&resolved.types),
 	};
 
 	let datapath = datapath.join("index.json");
@@ -239,7 +338,293 @@ hidetitle = true
 		name = module.header.name
 	);
 
-	let templatepath = templatepath.join(format!("_index.md"));
+	let templatepath = templatepath.join("_index.md");
 	std::fs::write(&templatepath, template)
-		.with_context(|| format!("while writing {templatepath:?}"))
+		.with_context(|| format!("while writing {templatepath:?}"))?;
+
+	Ok(resolved)
+}
+
+/// Prints every unresolved type, dangling link and mismatched override grouped by the type it
+/// was found on, failing on unresolved types or dangling links if `deny` is set.
+fn report(resolved: &[resolver::Resolved], deny: bool) -> anyhow::Result<()> {
+	let mut unresolved = resolved
+		.iter()
+		.flat_map(|r| r.unresolved.iter())
+		.collect::<Vec<_>>();
+	unresolved.sort_by(|a, b| Ord::cmp(&(&a.module, &a.class), &(&b.module, &b.class)));
+
+	let mut index = links::LinkIndex::new();
+	for r in resolved {
+		index.add(&r.types);
+	}
+
+	let mut dangling = resolved
+		.iter()
+		.flat_map(|r| index.check(&r.types))
+		.collect::<Vec<_>>();
+	dangling.sort_by(|a, b| Ord::cmp(&(&a.module, &a.class), &(&b.module, &b.class)));
+
+	let mut overrides = resolved
+		.iter()
+		.flat_map(|r| r.overrides.iter())
+		.collect::<Vec<_>>();
+	overrides.sort_by(|a, b| Ord::cmp(&(&a.module, &a.class), &(&b.module, &b.class)));
+
+	let print_source = |file: &str, line: usize| format!(" ({file}:{line})");
+
+	if !unresolved.is_empty() {
+		eprintln!("{} unresolved types:", unresolved.len());
+
+		let mut current = None;
+
+		for u in &unresolved {
+			if current != Some((&u.module, &u.class)) {
+				eprintln!("  {}.{}:", u.module, u.class);
+				current = Some((&u.module, &u.class));
+			}
+
+			let source = u
+				.source
+				.as_ref()
+				.map(|s| print_source(&s.file, s.line))
+				.unwrap_or_default();
+
+			eprintln!("    {}: `{}`{source}", u.member, u.name);
+		}
+	}
+
+	if !dangling.is_empty() {
+		eprintln!("{} dangling links:", dangling.len());
+
+		let mut current = None;
+
+		for d in &dangling {
+			if current != Some((&d.module, &d.class)) {
+				eprintln!("  {}.{}:", d.module, d.class);
+				current = Some((&d.module, &d.class));
+			}
+
+			let source = d
+				.source
+				.as_ref()
+				.map(|s| print_source(&s.file, s.line))
+				.unwrap_or_default();
+
+			eprintln!("    {}: `{}`, {}{source}", d.member, d.link, d.reason);
+		}
+	}
+
+	if !overrides.is_empty() {
+		eprintln!("{} mismatched overrides:", overrides.len());
+
+		let mut current = None;
+
+		for o in &overrides {
+			if current != Some((&o.module, &o.class)) {
+				eprintln!("  {}.{}:", o.module, o.class);
+				current = Some((&o.module, &o.class));
+			}
+
+			let source = o
+				.source
+				.as_ref()
+				.map(|s| print_source(&s.file, s.line))
+				.unwrap_or_default();
+
+			eprintln!(
+				"    {} of `{}`: overrides `{}`, {}{source}",
+				o.member, o.class_declaring, o.base, o.reason
+			);
+		}
+	}
+
+	if deny && !(unresolved.is_empty() && dangling.is_empty()) {
+		bail!(
+			"{} unresolved types and {} dangling links (--deny-unknown)",
+			unresolved.len(),
+			dangling.len()
+		);
+	}
+
+	Ok(())
+}
+
+fn lint(modinfos: &[&String], json: bool, threshold: Option<f64>) -> anyhow::Result<()> {
+	let coverage = modinfos
+		.iter()
+		.map(|modinfo| {
+			// an empty root keeps paths as given, so they can be opened directly
+			parse_sources(modinfo, Some(""), |_, ctx| Ok(lint::lint(ctx)))
+				.with_context(|| format!("while linting {modinfo}"))
+		})
+		.collect::<anyhow::Result<Vec<_>>>()?;
+
+	match json {
+		true => println!("{}", serde_json::to_string_pretty(&coverage).unwrap()),
+		false => {
+			for module in &coverage {
+				for missing in &module.missing {
+					println!("{}:{}: {}", missing.file, missing.line, missing);
+				}
+			}
+
+			for module in &coverage {
+				println!(
+					"{}: {:.1}% documented ({}/{})",
+					module.module, module.coverage, module.documented, module.total
+				);
+			}
+		},
+	}
+
+	if let Some(threshold) = threshold {
+		let failing = coverage
+			.iter()
+			.filter(|module| module.coverage < threshold)
+			.map(|module| format!("{} ({:.1}%)", module.module, module.coverage))
+			.collect::<Vec<_>>();
+
+		if !failing.is_empty() {
+			bail!(
+				"documentation coverage below {threshold}% in {}",
+				failing.join(", ")
+			);
+		}
+	}
+
+	Ok(())
+}
+
+fn genqmltypes(modinfo: &str, outpath: &str, typepaths: &[String]) -> anyhow::Result<()> {
+	let text = std::fs::read_to_string(modinfo).expect("failed to read module file");
+	let module = parse::parse_module(&text)?;
+	let typespec = load_typespecs(typepaths)?;
+
+	let name = &module.header.name;
+	let version = module
+		.header
+		.version
+		.split_once('.')
+		.and_then(|(major, minor)| Some((major.parse().ok()?, minor.parse().ok()?)))
+		.ok_or_else(|| anyhow!("invalid module version `{}`", module.header.version))?;
+
+	let typeinfo = format!("{name}.qmltypes");
+	let (qmltypes, qmldir) = qmltypes::gen_qmltypes(name, version, &typeinfo, &typespec);
+
+	// qml modules are looked up by their uri, e.g. `Quickshell.Wayland` in `Quickshell/Wayland`
+	let outpath = name
+		.split('.')
+		.fold(Path::new(outpath).to_path_buf(), |path, part| {
+			path.join(part)
+		});
+	std::fs::create_dir_all(&outpath)?;
+
+	let qmltypespath = outpath.join(&typeinfo);
+	std::fs::write(&qmltypespath, qmltypes)
+		.with_context(|| format!("while writing {qmltypespath:?}"))?;
+
+	let qmldirpath = outpath.join("qmldir");
+	std::fs::write(&qmldirpath, qmldir).with_context(|| format!("while writing {qmldirpath:?}"))?;
+
+	// qmldir lists the module's qml files by name, which tooling expects next to it
+	let dir = Path::new(modinfo).parent().unwrap();
+	for file in &module.header.qml_files {
+		let from = dir.join(file);
+		let to = outpath.join(Path::new(file).file_name().unwrap());
+		std::fs::copy(&from, &to).with_context(|| format!("while copying {from:?} to {to:?}"))?;
+	}
+
+	Ok(())
+}
+
+fn gendiff(old: &str, new: &str, json: bool) -> anyhow::Result<()> {
+	let old = load_typespecs(&typespec_files(old)?)?;
+	let new = load_typespecs(&typespec_files(new)?)?;
+
+	let diffs = diff::diff(&old, &new);
+
+	match json {
+		true => println!("{}", serde_json::to_string_pretty(&diffs).unwrap()),
+		false => print!("{}", diff::to_markdown(&diffs)),
+	}
+
+	Ok(())
+}
+
+/// Lists the typespec files in a directory, or the path itself if it is a file.
+fn typespec_files(path: &str) -> anyhow::Result<Vec<String>> {
+	let path = Path::new(path);
+
+	if !path.is_dir() {
+		return Ok(vec![path.to_string_lossy().to_string()])
+	}
+
+	let mut files = path
+		.read_dir()
+		.with_context(|| format!("while reading {path:?}"))?
+		.flatten()
+		.map(|e| e.path())
+		.filter(|path| path.extension().map(|ext| ext == "json").unwrap_or(false))
+		.map(|path| path.to_string_lossy().to_string())
+		.collect::<Vec<_>>();
+
+	files.sort();
+	Ok(files)
+}
+
+fn load_typespecs(typepaths: &[String]) -> anyhow::Result<typespec::TypeSpec> {
+	let mut typespec = typespec::TypeSpec::default();
+
+	for path in typepaths {
+		let text =
+			std::fs::read_to_string(path).with_context(|| anyhow!("attempting to read {path}"))?;
+
+		let ts = serde_json::from_str::<typespec::TypeSpec>(&text)
+			.with_context(|| anyhow!("attempting to parse {path}"))?;
+
+		typespec.typemap.extend(ts.typemap);
+		typespec.classes.extend(ts.classes);
+		typespec.gadgets.extend(ts.gadgets);
+		typespec.enums.extend(ts.enums);
+	}
+
+	Ok(typespec)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::reformat::{Context, ReformatPass, TypeLinks};
+
+	/// Resolves a type whose description contains `description`.
+	fn resolve(description: &str) -> resolver::Resolved {
+		let mut description = description.to_string();
+		TypeLinks.reformat(
+			&Context {
+				module: testutil::MODULE,
+			},
+			&mut description,
+		);
+
+		let mut thing = testutil::class("Thing", "QQuickItem");
+		thing.description = Some(description);
+		thing.properties = vec![testutil::property("foo", "qint32")];
+
+		let typespec = testutil::typespec(vec![thing]);
+		resolver::resolve_types(testutil::MODULE, &[], typespec, parse::MemberOrder::Source)
+			.unwrap()
+	}
+
+	#[test]
+	fn deny_unknown() {
+		assert!(report(&[resolve("@@Thing.foo")], true).is_ok());
+		assert!(report(&[resolve("@@Thing.bar")], false).is_ok());
+
+		let err = report(&[resolve("@@Thing.bar")], true).unwrap_err();
+		assert_eq!(
+			err.to_string(),
+			"0 unresolved types and 1 dangling links (--deny-unknown)"
+		);
+	}
 }
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 use serde::Serialize;
 
@@ -7,6 +7,26 @@ pub struct ModuleIndex {
 	pub name: String,
 	pub description: String,
 	pub details: String,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub deprecated: Vec<DeprecatedApi>,
+}
+
+/// A deprecated type or member, in the form taken by the qmltype partial.
+#[derive(Debug, Serialize)]
+pub struct DeprecatedApi {
+	#[serde(rename = "type")]
+	pub type_: TypeSource,
+	pub module: String,
+	pub name: String,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub mtype: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub mname: Option<String>,
+	/// anchor of the member, if it differs from its name
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub id: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub replacement: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -21,8 +41,8 @@ pub struct TypeInfo {
 #[serde(rename_all = "lowercase")]
 #[serde(tag = "type")]
 pub enum TypeDetails {
-	Class(ClassInfo),
-	Enum(EnumInfo),
+	Class(Box<ClassInfo>),
+	Enum(Box<EnumInfo>),
 }
 
 #[derive(Debug, Serialize)]
@@ -33,25 +53,70 @@ pub struct ClassInfo {
 	pub details: Option<String>,
 	#[serde(skip_serializing_if = "Vec::is_empty")]
 	pub flags: Vec<Flag>,
-	pub properties: HashMap<String, Property>,
+	/// type link to the api replacing this one, if deprecated
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub replacement: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub source: Option<SourceLocation>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub since: Option<Since>,
+	pub properties: Vec<Property>,
+	pub functions: Vec<Function>,
+	pub signals: Vec<Signal>,
+	pub variants: BTreeMap<String, Variant>,
+	/// members of ancestors visible from QML, nearest first
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub inherited: Vec<InheritedInfo>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub attached: Option<AttachedInfo>,
+}
+
+/// Members inherited from an ancestor visible from QML, documented on its own page.
+#[derive(Debug, Serialize)]
+pub struct InheritedInfo {
+	#[serde(rename = "type")]
+	pub type_: Type,
+	pub properties: Vec<String>,
+	/// ids of the functions, see `Function::id`
+	pub functions: Vec<String>,
+	pub signals: Vec<String>,
+}
+
+/// Members of the attached object, used as `Type.member` from within other objects.
+#[derive(Debug, Serialize)]
+pub struct AttachedInfo {
+	pub properties: Vec<Property>,
 	pub functions: Vec<Function>,
-	pub signals: HashMap<String, Signal>,
-	pub variants: HashMap<String, Variant>,
+	pub signals: Vec<Signal>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct Property {
+	pub name: String,
 	#[serde(rename = "type")]
 	pub type_: PropertyType,
+	/// section from a `@group` comment, when members are grouped
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub group: Option<String>,
+	/// c++ class declaring this member, if inherited from a superclass not visible from QML
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub inherited_from: Option<String>,
 	pub details: Option<String>,
 	#[serde(skip_serializing_if = "Vec::is_empty")]
 	pub flags: Vec<Flag>,
+	/// type link to the api replacing this one, if deprecated
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub replacement: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub source: Option<SourceLocation>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub since: Option<Since>,
 }
 
 #[derive(Debug, Serialize)]
 pub enum PropertyType {
 	#[serde(rename = "gadget")]
-	Gadget(HashMap<String, PropertyType>),
+	Gadget(BTreeMap<String, PropertyType>),
 	#[serde(untagged)]
 	Type(Type),
 }
@@ -60,16 +125,59 @@ pub enum PropertyType {
 pub struct Function {
 	pub ret: Type,
 	pub name: String,
+	/// unique anchor, the name followed by the qml parameter types if overloaded
 	pub id: String,
+	/// parameter types as declared, identifying the overload in typespec snapshots
+	#[serde(skip)]
+	pub signature: String,
+	/// additional anchor selecting this overload by its parameter count, if unique
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub arity_id: Option<String>,
+	/// additional anchor selecting the first listed overload by the name alone
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub name_id: Option<String>,
+	/// ids of the other overloads of this function
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub overloads: Vec<String>,
+	/// section from a `@group` comment, when members are grouped
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub group: Option<String>,
+	/// c++ class declaring this member, if inherited from a superclass not visible from QML
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub inherited_from: Option<String>,
 	pub details: Option<String>,
 	pub params: Vec<Parameter>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub flags: Vec<Flag>,
+	/// type link to the api replacing this one, if deprecated
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub replacement: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub source: Option<SourceLocation>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub since: Option<Since>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct Signal {
 	pub name: String,
+	/// section from a `@group` comment, when members are grouped
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub group: Option<String>,
+	/// c++ class declaring this member, if inherited from a superclass not visible from QML
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub inherited_from: Option<String>,
 	pub details: Option<String>,
 	pub params: Vec<Parameter>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub flags: Vec<Flag>,
+	/// type link to the api replacing this one, if deprecated
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub replacement: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub source: Option<SourceLocation>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub since: Option<Since>,
 }
 
 #[derive(Debug, Serialize)]
@@ -77,18 +185,56 @@ pub struct Parameter {
 	pub name: String,
 	#[serde(rename = "type")]
 	pub type_: Type,
+	/// default value as written in the source, making the parameter optional
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub default: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct EnumInfo {
 	pub description: Option<String>,
 	pub details: Option<String>,
-	pub variants: HashMap<String, Variant>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub flags: Vec<Flag>,
+	/// type link to the api replacing this one, if deprecated
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub replacement: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub source: Option<SourceLocation>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub since: Option<Since>,
+	pub variants: BTreeMap<String, Variant>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct Variant {
 	pub details: Option<String>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub flags: Vec<Flag>,
+	/// type link to the api replacing this one, if deprecated
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub replacement: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub since: Option<Since>,
+}
+
+/// Release history of a type or member.
+#[derive(Debug, Serialize)]
+pub struct Since {
+	/// first release containing it
+	pub version: String,
+	/// first release with its current signature, if that differs from `version`
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub changed: Option<String>,
+	/// the signature changed after the latest release
+	#[serde(skip_serializing_if = "std::ops::Not::not")]
+	pub unreleased_change: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceLocation {
+	pub file: String,
+	pub line: usize,
 }
 
 #[derive(Debug, Serialize)]
@@ -97,23 +243,23 @@ pub struct Type {
 	pub type_: TypeSource,
 	pub module: String,
 	pub name: String,
-	#[serde(skip_serializing_if = "Option::is_none")]
-	pub of: Option<Box<Type>>,
+	/// type arguments, e.g. the element type of a list or the key and value types of a map
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub args: Vec<Type>,
+	/// whether the value may be null, from `@nullable` tags or the module's pointer default
+	#[serde(skip_serializing_if = "std::ops::Not::not")]
+	pub nullable: bool,
 }
 
 impl Type {
-	pub fn resolve(module: Option<&str>, name: &str) -> Self {
-		let (src, module) = match module {
-			None => (TypeSource::Qt, "qml".to_string()),
-			Some(name) if name.starts_with("qml.") => (TypeSource::Qt, name.to_string()),
-			Some(name) => (TypeSource::Local, name.to_string()),
-		};
-
+	/// A type registered in `module`, or a QML builtin if there is none.
+	pub fn resolve(src: TypeSource, module: Option<&str>, name: &str) -> Self {
 		Type {
 			type_: src,
-			module,
+			module: module.unwrap_or("qml").to_string(),
 			name: name.to_string(),
-			of: None,
+			args: Vec::new(),
+			nullable: false,
 		}
 	}
 
@@ -122,7 +268,8 @@ impl Type {
 			type_: TypeSource::Unknown,
 			module: "".to_string(),
 			name: "".to_string(),
-			of: None,
+			args: Vec::new(),
+			nullable: false,
 		}
 	}
 }
@@ -132,6 +279,8 @@ impl Type {
 pub enum TypeSource {
 	Qt,
 	Local,
+	/// a `QML_ANONYMOUS` type, which has no page to link to
+	Anonymous,
 	Unknown,
 }
 
@@ -144,4 +293,5 @@ pub enum Flag {
 	Singleton,
 	Uncreatable,
 	Enum,
+	Deprecated,
 }
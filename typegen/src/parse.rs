@@ -1,10 +1,13 @@
-use std::{borrow::Cow, collections::HashMap};
+use std::{borrow::Cow, collections::HashMap, path::Path};
 
 use anyhow::{anyhow, bail, Context};
 use fancy_regex::Regex;
 use serde::Deserialize;
 
 use crate::{
+	cpp,
+	location::{Location, Source},
+	qml,
 	reformat::{self, ReformatPass},
 	typespec,
 };
@@ -17,6 +20,35 @@ pub struct ModuleInfoHeader {
 	pub headers: Vec<String>,
 	#[serde(default)]
 	pub qml_files: Vec<String>,
+	/// whether c++ pointers may be null unless marked `@nonnull`
+	#[serde(default)]
+	pub nullable_pointers: bool,
+	#[serde(default)]
+	pub member_order: MemberOrder,
+	/// QML modules `QML:` type names may refer to besides this one, e.g. `QtQuick`,
+	/// or every module if empty
+	#[serde(default)]
+	pub imports: Vec<String>,
+	/// version types are exported with in generated qmltypes, e.g. `1.0`
+	#[serde(default = "default_version")]
+	pub version: String,
+}
+
+fn default_version() -> String {
+	"1.0".to_string()
+}
+
+/// Order members are documented in.
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MemberOrder {
+	/// declaration order
+	Source,
+	/// alphabetical order
+	#[default]
+	Alpha,
+	/// declaration order, split into sections by `/// @group Name` comments
+	Grouped,
 }
 
 #[derive(Debug)]
@@ -25,7 +57,7 @@ pub struct ModuleInfo<'a> {
 	pub details: &'a str,
 }
 
-pub fn parse_module(text: &str) -> anyhow::Result<ModuleInfo> {
+pub fn parse_module(text: &str) -> anyhow::Result<ModuleInfo<'_>> {
 	let Some((mut header, mut details)) = text.split_once("-----") else {
 		bail!("could not split module header");
 	};
@@ -54,18 +86,81 @@ impl<'a> Comment<'a> {
 #[derive(Debug)]
 pub struct ClassInfo<'a> {
 	pub type_: ClassType,
-	pub name: &'a str,
-	pub qml_name: Option<&'a str>,
+	pub name: Cow<'a, str>,
+	pub qml_name: Option<Cow<'a, str>>,
 	pub superclass: Option<Cow<'a, str>>,
 	pub singleton: bool,
 	pub uncreatable: bool,
+	/// registered with `QML_ANONYMOUS`, usable from QML without a name
+	pub anonymous: bool,
+	/// class the registration macros apply to instead, from `QML_FOREIGN`
+	pub foreign: Option<&'a str>,
+	/// class whose members are added to this one in QML, from `QML_EXTENDED`
+	pub extension: Option<&'a str>,
 	pub comment: Option<Comment<'a>>,
+	pub location: Location<'a>,
+	pub deprecated: Option<Deprecation<'a>>,
+	/// class providing the attached object, from `QML_ATTACHED` or `qmlAttachedProperties`
+	pub attached: Option<&'a str>,
 	pub properties: Vec<Property<'a>>,
 	pub invokables: Vec<Invokable<'a>>,
 	pub signals: Vec<Signal<'a>>,
 	pub enums: Vec<EnumInfo<'a>>,
 }
 
+impl<'a> ClassInfo<'a> {
+	/// Numbers members in declaration order and assigns each the `@group` section it is in.
+	///
+	/// A `/// @group Name` line in a member's comment starts a section containing it and every
+	/// following member, and a bare `/// @group` ends it.
+	fn order_members(&mut self) {
+		let mut members = Vec::new();
+
+		members.extend(
+			self.properties
+				.iter_mut()
+				.map(|p| (p.location, p.comment, &mut p.index, &mut p.group)),
+		);
+
+		members.extend(
+			self.invokables
+				.iter_mut()
+				.map(|f| (f.location, f.comment, &mut f.index, &mut f.group)),
+		);
+
+		members.extend(
+			self.signals
+				.iter_mut()
+				.map(|s| (s.location, s.comment, &mut s.index, &mut s.group)),
+		);
+
+		members.sort_by_key(|(location, ..)| (location.line, location.col));
+
+		let mut current = None;
+
+		for (i, (_, comment, index, group)) in members.into_iter().enumerate() {
+			let marker = comment.and_then(|comment| {
+				comment.text.lines().find_map(|line| {
+					let line = line.trim().trim_start_matches('/').trim_start();
+					let name = line.strip_prefix("@group")?;
+
+					match name.chars().next() {
+						Some(c) if !c.is_whitespace() => None,
+						_ => Some(Some(name.trim()).filter(|name| !name.is_empty())),
+					}
+				})
+			});
+
+			if let Some(marker) = marker {
+				current = marker;
+			}
+
+			*index = i;
+			*group = current;
+		}
+	}
+}
+
 #[derive(Debug)]
 pub enum ClassType {
 	Object,
@@ -77,16 +172,28 @@ pub struct Property<'a> {
 	pub type_: Cow<'a, str>,
 	pub name: &'a str,
 	pub comment: Option<Comment<'a>>,
+	pub location: Location<'a>,
+	pub deprecated: Option<Deprecation<'a>>,
+	/// position among all members of the class, see `ClassInfo::order_members`
+	pub index: usize,
+	pub group: Option<&'a str>,
 	pub readable: bool,
 	pub writable: bool,
 	pub default: bool,
+	/// signal emitted when the property changes, from `NOTIFY`
+	pub notify: Option<&'a str>,
 }
 
 #[derive(Debug, Clone)]
 pub struct Invokable<'a> {
 	pub name: &'a str,
-	pub ret: &'a str,
+	pub ret: Cow<'a, str>,
 	pub comment: Option<Comment<'a>>,
+	pub location: Location<'a>,
+	pub deprecated: Option<Deprecation<'a>>,
+	/// position among all members of the class, see `ClassInfo::order_members`
+	pub index: usize,
+	pub group: Option<&'a str>,
 	pub params: Vec<InvokableParam<'a>>,
 }
 
@@ -94,13 +201,20 @@ pub struct Invokable<'a> {
 pub struct Signal<'a> {
 	pub name: &'a str,
 	pub comment: Option<Comment<'a>>,
+	pub location: Location<'a>,
+	pub deprecated: Option<Deprecation<'a>>,
+	/// position among all members of the class, see `ClassInfo::order_members`
+	pub index: usize,
+	pub group: Option<&'a str>,
 	pub params: Vec<InvokableParam<'a>>,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct InvokableParam<'a> {
 	pub name: &'a str,
-	pub type_: &'a str,
+	pub type_: Cow<'a, str>,
+	/// default value as written in the source, making the param optional
+	pub default: Option<&'a str>,
 }
 
 #[derive(Debug)]
@@ -109,50 +223,71 @@ pub struct EnumInfo<'a> {
 	pub enum_name: &'a str,
 	pub qml_name: &'a str,
 	pub comment: Option<Comment<'a>>,
+	pub location: Location<'a>,
+	pub deprecated: Option<Deprecation<'a>>,
 	pub variants: Vec<Variant<'a>>,
 }
 
+/// Deprecation from a `QSDOC_DEPRECATED` macro or `[[deprecated]]` attribute.
+///
+/// `/// @deprecated` comment tags are read from the comment when generating the typespec.
+#[derive(Debug, Clone)]
+pub struct Deprecation<'a> {
+	/// `@@` link to the api replacing the deprecated one
+	pub replacement: Option<Cow<'a, str>>,
+	pub module: &'a str,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct Variant<'a> {
 	pub name: &'a str,
 	pub comment: Option<Comment<'a>>,
+	pub location: Location<'a>,
 }
 
 pub struct CppParser {
-	pub class_regex: Regex,
 	pub macro_regex: Regex,
+	pub member_macro_regex: Regex,
 	pub property_regex: Regex,
-	pub signals_regex: Regex,
 	pub fn_regex: Regex,
+	pub slot_regex: Regex,
 	pub signal_regex: Regex,
 	pub fn_param_regex: Regex,
 	pub defaultprop_classinfo_regex: Regex,
 	pub enum_ns_regex: Regex,
-	pub enum_class_regex: Regex,
+	pub enum_regex: Regex,
 	pub enum_variant_regex: Regex,
+	pub deprecated_regex: Regex,
+	pub attribute_regex: Regex,
+	pub deprecated_attr_regex: Regex,
+	pub attached_fn_regex: Regex,
 }
 
 pub struct QmlParser {
-	pub class_regex: Regex,
 	pub alias_regex: Regex,
-	pub safe_body_regex: Regex,
-	pub property_regex: Regex,
-	pub function_regex: Regex,
+	pub function_param_regex: Regex,
+	pub signal_param_regex: Regex,
 }
 
 #[derive(Debug)]
 pub struct ParseContext<'a> {
 	pub module: &'a str,
+	/// default nullability of c++ pointers, see `ModuleInfoHeader::nullable_pointers`
+	pub nullable_pointers: bool,
 	pub classes: Vec<ClassInfo<'a>>,
 	pub enums: Vec<EnumInfo<'a>>,
+	/// problems that did not stop parsing, reported by the caller
+	pub warnings: Vec<String>,
 }
 
 impl<'a> ParseContext<'a> {
 	pub fn new(module: &'a str) -> Self {
 		Self {
 			module,
+			nullable_pointers: false,
 			classes: Vec::new(),
 			enums: Vec::new(),
+			warnings: Vec::new(),
 		}
 	}
 }
@@ -160,149 +295,293 @@ impl<'a> ParseContext<'a> {
 impl CppParser {
 	pub fn new() -> Self {
 		Self {
-			class_regex: Regex::new(r#"(?<comment>(\s*\/\/\/.*\n)+)?\s*class\s+(?<name>\w+)(?:\s*:\s*public\s+((?<super>\w+)(<.+>)?)(\s*,(\s*\w+)*)*)?\s*\{(?<body>[\s\S]*?)(?!};\s*Q_ENUM)};"#).unwrap(),
 			macro_regex: Regex::new(r#"(?<comment>(\s*\/\/\/.*\n)+)?\s*(?<hide>QSDOC_HIDE\s)?(?<type>(Q|QML|QSDOC)_\w+)\s*(\(\s*(?<args>.*)\s*\))?;"#).unwrap(),
-			property_regex: Regex::new(r#"^\s*(?<type>(\w|::|, |<|>|\*)+)\*?\s+(?<name>\w+)(\s+(MEMBER\s+(?<member>\w+)|READ\s+(?<read>\w+)|WRITE\s+(?<write>\w+)|RESET\s+(?<reset>\w+)|NOTIFY\s+(?<notify>\w+)|BINDABLE\s+(?<bindable>\w+)|(?<const>CONSTANT)|(?<final>FINAL)))+\s*$"#).unwrap(),
-			fn_regex: Regex::new(r#"(?<comment>(\s*\/\/\/.*\n)+)?\s*(?<hide>QSDOC_HIDE\s)?(\/\/@\s+)?Q_INVOKABLE\s+(\[\[.*\]\]\s+)?(static\s+)?(?<type>(\w|::|<|>)+\*?)\s+(?<name>\w+)\((?<params>[\s\S]*?)\)(\s*const)?;"#).unwrap(),
-			signal_regex: Regex::new(r#"(?<comment>(\s*\/\/\/.*\n)+)?\s*void\s+(?<name>\w+)\((?<params>[\s\S]*?)\);"#).unwrap(),
-			fn_param_regex: Regex::new(r#"(const\s+)?(?<type>(\w|::|<|>)+\*?)&?\s+(?<name>\w+)(,|$)"#).unwrap(),
-			signals_regex: Regex::new(r#"signals:(?<signals>(\s*(\s*///.*\s*)*void .*;)*)"#).unwrap(),
+			member_macro_regex: Regex::new(r#"^(?<hide>QSDOC_HIDE\s+)?(?<type>(Q|QML|QSDOC)_\w+)\s*(\(\s*(?<args>[\s\S]*?)\s*\))?$"#).unwrap(),
+			property_regex: Regex::new(r#"^\s*(?<type>(\w|::)+(\s*<[^()]*>)?(\s*\*+)?)(\s+|(?<=\*))(?<name>\w+)(\s+(MEMBER\s+(?<member>\w+)|READ\s+(?<read>\w+)|WRITE\s+(?<write>\w+)|RESET\s+(?<reset>\w+)|NOTIFY\s+(?<notify>\w+)|BINDABLE\s+(?<bindable>\w+)|(?<const>CONSTANT)|(?<final>FINAL)))+\s*$"#).unwrap(),
+			fn_regex: Regex::new(r#"^(?<hide>QSDOC_HIDE\s+)?Q_INVOKABLE\s+(\[\[(?<attr>.*)\]\]\s+)?((virtual|static|inline)\s+)*(const\s+)?(?<type>(\w|::)+(\s*<[^()]*>)?(\s*\*+)?)\s*&{0,2}(\s+|(?<=[*&]))(?<name>\w+)\s*\((?<params>[\s\S]*)\)(\s*const)?(\s*(override|final|noexcept))*$"#).unwrap(),
+			slot_regex: Regex::new(r#"^(?<hide>QSDOC_HIDE\s+)?(?<slot>Q_SLOT\s+)?(\[\[(?<attr>.*)\]\]\s+)?((virtual|static|inline)\s+)*(const\s+)?(?<type>(\w|::)+(\s*<[^()]*>)?(\s*\*+)?)\s*&{0,2}(\s+|(?<=[*&]))(?<name>\w+)\s*\((?<params>[\s\S]*)\)(\s*const)?(\s*(override|final|noexcept))*$"#).unwrap(),
+			signal_regex: Regex::new(r#"^(?<hide>QSDOC_HIDE\s+)?(?<marker>Q_SIGNAL\s+)?void\s+(?<name>\w+)\s*\((?<params>[\s\S]*)\)$"#).unwrap(),
+			fn_param_regex: Regex::new(r#"^(const\s+)?(?<type>[\s\S]*?[\w>*])\s*&{0,2}\s*(?<name>\w+)$"#).unwrap(),
 			defaultprop_classinfo_regex: Regex::new(r#"^\s*"DefaultProperty", "(?<prop>.+)"\s*$"#).unwrap(),
 			enum_ns_regex: Regex::new(r#"(?<comment>(\s*\/\/\/.*\n)+)?\s*namespace (?<namespace>\w+)\s*\{(?<nsbody>[\s\S]*?(QML_ELEMENT|QML_NAMED_ELEMENT\((?<qml_name>\w+)\));[\s\S]*?enum\s*(?<enum_name>\w+)\s*(:\s*\w+\s*)?\{(?<body>[\s\S]*?)\};[\s\S]*?)\}"#).unwrap(),
-			enum_class_regex: Regex::new(r#"(?<comment>(\s*\/\/\/.*\n)+)?\s*enum\s*(?<enum_name>\w+)\s*(:\s*\w+\s*)?\{(?<body>[\s\S]*?)\};\s+Q_ENUM\(.+\);"#).unwrap(),
+			enum_regex: Regex::new(r#"^enum\s+(class\s+)?(?<enum_name>\w+)\s*(:\s*[\w:]+\s*)?$"#).unwrap(),
 			enum_variant_regex: Regex::new(r#"(?<comment>(\s*\/\/\/.*\n)+)?\s*(?<name>\w+)\s*=\s*.+,"#).unwrap(),
+			deprecated_regex: Regex::new(r#"^QSDOC_DEPRECATED(\s*\((?<replacement>.*?)\))?\s+(?<rest>[\s\S]*)$"#).unwrap(),
+			attribute_regex: Regex::new(r#"^\[\[(?<attr>.*?)\]\]\s+(?<rest>[\s\S]*)$"#).unwrap(),
+			deprecated_attr_regex: Regex::new(r#"^deprecated(\s*\(\s*"(?<message>.*)"\s*\))?$"#).unwrap(),
+			attached_fn_regex: Regex::new(r#"^static\s+(?<type>(\w|::)+)\s*\*\s*qmlAttachedProperties\s*\([\s\S]*\)$"#).unwrap(),
 		}
 	}
 
 	pub fn parse_classes<'a>(
 		&self,
-		text: &'a str,
+		src: Source<'a>,
 		ctx: &mut ParseContext<'a>,
 	) -> anyhow::Result<()> {
-		for class in self.class_regex.captures_iter(text) {
-			let class = class?;
-
-			let comment = class.name("comment").map(|m| m.as_str());
-			let mut name = class.name("name").unwrap().as_str();
-			let mut superclass = class.name("super").map(|m| m.as_str());
-			let body = class.name("body").unwrap().as_str();
+		for class in cpp::find_classes(src)? {
+			let location = src.locate(class.name);
+			let comment = class.comment;
+			let mut name = class.name;
+			let mut superclass = class.superclass;
 
 			let mut classtype = None;
 			let mut qml_name = None;
+			let mut named = false;
 			let mut singleton = false;
 			let mut uncreatable = false;
+			let mut anonymous = false;
+			let mut foreign = None;
+			let mut extension = None;
 			let mut force_creatable = false;
 			let mut properties = Vec::new();
 			let mut default_property = None;
 			let mut invokables = Vec::new();
 			let mut notify_signals = Vec::new();
 			let mut signals = Vec::new();
+			let mut declared_enums = Vec::<(&str, Option<&str>, &str, &str)>::new();
 			let mut enums = Vec::new();
+			let mut deprecated_class = None;
+			let mut attached = None;
+			let mut attached_fn = None;
 
 			struct Carryover<'a> {
 				type_override: Option<&'a str>,
 				comment: Option<&'a str>,
+				deprecated: Option<Deprecation<'a>>,
 			}
 
 			let mut carryover = Option::<Carryover<'a>>::None;
 
 			(|| {
-				for macro_ in self.macro_regex.captures_iter(body) {
-					let macro_ = macro_?;
+				for decl in &class.decls {
+					let (deprecated, text) = self.parse_deprecation(decl.text, ctx.module)?;
 
-					if macro_.name("hide").is_some() {
-						continue
-					}
+					if let Some(macro_) = self.member_macro_regex.captures(text)? {
+						if macro_.name("hide").is_some() {
+							continue
+						}
+
+						let comment = decl.comment.or(carryover.as_ref().and_then(|c| c.comment));
+						let type_ = macro_.name("type").unwrap().as_str();
+						let args = macro_.name("args").map(|m| m.as_str());
+
+						let this_carryover = carryover.take();
+
+						// deprecating any of the registration macros deprecates the class
+						if matches!(
+							type_,
+							"Q_OBJECT"
+								| "Q_GADGET" | "QML_ELEMENT"
+								| "QML_NAMED_ELEMENT" | "QSDOC_ELEMENT"
+								| "QSDOC_NAMED_ELEMENT"
+						) && deprecated.is_some()
+						{
+							deprecated_class = deprecated.clone();
+						}
 
-					let comment = macro_
-						.name("comment")
-						.map(|m| m.as_str())
-						.or(carryover.as_ref().map(|c| c.comment).flatten());
-					let type_ = macro_.name("type").unwrap().as_str();
-					let args = macro_.name("args").map(|m| m.as_str());
-
-					let this_carryover = carryover.take();
-
-					(|| {
-						match type_ {
-							"QSDOC_BASECLASS" => {
-								superclass = Some(args.expect(
-									"QSDOC_BASECLASS must have the base class as an argument",
-								))
-							},
-							"QSDOC_CNAME" => {
-								name = args
-									.expect("QSDOC_CNAME must specify the cname as an argument");
-							},
-							"Q_OBJECT" => classtype = Some(ClassType::Object),
-							"Q_GADGET" => classtype = Some(ClassType::Gadget),
-							"QML_ELEMENT" | "QSDOC_ELEMENT" => qml_name = Some(name),
-							"QML_NAMED_ELEMENT" | "QSDOC_NAMED_ELEMENT" => {
-								qml_name = Some(args.ok_or_else(|| {
-									anyhow!("expected name for QML_NAMED_ELEMENT")
-								})?)
-							},
-							"QML_SINGLETON" => singleton = true,
-							"QML_UNCREATABLE" => uncreatable = true,
-							"QSDOC_CREATABLE" => force_creatable = true,
-							"QSDOC_TYPE_OVERRIDE" => {
-								let type_override = args
-									.ok_or_else(|| anyhow!("expected param for QSDOC_GENERIC"))?;
-
-								carryover = Some(Carryover {
-									type_override: Some(type_override),
-									comment,
-								});
-							},
-							"Q_PROPERTY" | "QSDOC_PROPERTY_OVERRIDE" => {
-								let prop =
-									self.property_regex
+						(|| {
+							match type_ {
+								"QSDOC_BASECLASS" => {
+									superclass = Some(args.expect(
+										"QSDOC_BASECLASS must have the base class as an argument",
+									))
+								},
+								"QSDOC_CNAME" => {
+									name = args.expect(
+										"QSDOC_CNAME must specify the cname as an argument",
+									);
+								},
+								"Q_OBJECT" => classtype = Some(ClassType::Object),
+								"Q_GADGET" => classtype = Some(ClassType::Gadget),
+								"QML_ELEMENT" | "QSDOC_ELEMENT" => qml_name = Some(name),
+								"QML_NAMED_ELEMENT" | "QSDOC_NAMED_ELEMENT" => {
+									qml_name = Some(args.ok_or_else(|| {
+										anyhow!("expected name for QML_NAMED_ELEMENT")
+									})?);
+									named = true;
+								},
+								"QML_ANONYMOUS" => anonymous = true,
+								"QML_FOREIGN" => {
+									foreign = Some(args.ok_or_else(|| {
+										anyhow!("expected foreign type for QML_FOREIGN")
+									})?)
+								},
+								"QML_EXTENDED" => {
+									extension = Some(args.ok_or_else(|| {
+										anyhow!("expected extension type for QML_EXTENDED")
+									})?)
+								},
+								"QML_SINGLETON" => singleton = true,
+								"QML_UNCREATABLE" => uncreatable = true,
+								"QML_ATTACHED" => {
+									attached = Some(args.ok_or_else(|| {
+										anyhow!("expected attached type for QML_ATTACHED")
+									})?)
+								},
+								"QSDOC_CREATABLE" => force_creatable = true,
+								"QSDOC_TYPE_OVERRIDE" => {
+									let type_override = args.ok_or_else(|| {
+										anyhow!("expected param for QSDOC_GENERIC")
+									})?;
+
+									carryover = Some(Carryover {
+										type_override: Some(type_override),
+										comment,
+										deprecated: deprecated.clone(),
+									});
+								},
+								"Q_PROPERTY" | "QSDOC_PROPERTY_OVERRIDE" => {
+									let prop = self
+										.property_regex
 										.captures(args.ok_or_else(|| {
 											anyhow!("expected args for Q_PROPERTY")
 										})?)?
 										.ok_or_else(|| anyhow!("unable to parse Q_PROPERTY"))?;
 
-								let member = prop.name("member").is_some();
-								let read = prop.name("read").is_some();
-								let write = prop.name("write").is_some();
-								let constant = prop.name("const").is_some();
+									let member = prop.name("member").is_some();
+									let read = prop.name("read").is_some();
+									let write = prop.name("write").is_some();
+									let constant = prop.name("const").is_some();
 
-								if let Some(notify) = prop.name("notify").map(|v| v.as_str()) {
-									notify_signals.push(notify);
-								}
+									let notify = prop.name("notify").map(|v| v.as_str());
+									if let Some(notify) = notify {
+										notify_signals.push(notify);
+									}
 
-								properties.push(Property {
-									type_: Cow::Borrowed(
-										this_carryover
+									properties.push(Property {
+										type_: this_carryover
 											.as_ref()
-											.map(|c| c.type_override)
-											.flatten()
-											.unwrap_or_else(|| prop.name("type").unwrap().as_str()),
-									),
-									name: prop.name("name").unwrap().as_str(),
-									comment: comment.map(|v| Comment::new(v, ctx.module)),
-									readable: read || member,
-									writable: !constant && (write || member),
-									default: false,
-								});
-							},
-							"Q_CLASSINFO" => {
-								let classinfo = self.defaultprop_classinfo_regex.captures(
-									args.ok_or_else(|| anyhow!("expected args for Q_CLASSINFO"))?,
-								)?;
-
-								if let Some(classinfo) = classinfo {
-									let prop = classinfo.name("prop").unwrap().as_str();
-									default_property = Some(prop);
-								}
-							},
-							_ => {},
+											.and_then(|c| c.type_override)
+											.map(Cow::Borrowed)
+											.unwrap_or_else(|| {
+												normalize_type(prop.name("type").unwrap().as_str())
+											}),
+										name: prop.name("name").unwrap().as_str(),
+										comment: comment.map(|v| Comment::new(v, ctx.module)),
+										location: src.locate(decl.text),
+										deprecated: deprecated.clone().or_else(|| {
+											this_carryover
+												.as_ref()
+												.and_then(|c| c.deprecated.clone())
+										}),
+										readable: read || member,
+										writable: !constant && (write || member),
+										default: false,
+										notify,
+										index: 0,
+										group: None,
+									});
+								},
+								"Q_CLASSINFO" => {
+									let classinfo = self.defaultprop_classinfo_regex.captures(
+										args.ok_or_else(|| {
+											anyhow!("expected args for Q_CLASSINFO")
+										})?,
+									)?;
+
+									if let Some(classinfo) = classinfo {
+										let prop = classinfo.name("prop").unwrap().as_str();
+										default_property = Some(prop);
+									}
+								},
+								"Q_ENUM" => {
+									let enum_name =
+										args.ok_or_else(|| anyhow!("expected args for Q_ENUM"))?;
+
+									let (comment, body, decl) = declared_enums
+										.iter()
+										.find(|(name, ..)| *name == enum_name)
+										.map(|(_, comment, body, decl)| (*comment, *body, *decl))
+										.ok_or_else(|| {
+											anyhow!("could not find enum `{enum_name}`")
+										})?;
+
+									enums.push(EnumInfo {
+										namespace: name,
+										enum_name,
+										qml_name: enum_name,
+										comment: comment.map(|v| Comment::new(v, ctx.module)),
+										location: src.locate(decl),
+										deprecated: deprecated.clone(),
+										variants: self.parse_enum_variants(src, body, ctx)?,
+									});
+								},
+								_ => {},
+							}
+							Ok::<_, anyhow::Error>(())
+						})()
+						.with_context(|| format!("while parsing macro `{}`", decl.text))?;
+					} else if let Some(invokable) = match self.fn_regex.captures(text)? {
+						Some(invokable) => Some(invokable),
+						// slots are callable from qml as well, but only public ones
+						None => self.slot_regex.captures(text)?.filter(|slot| {
+							decl.access == cpp::Access::Public
+								&& (decl.section == cpp::Section::Slots
+									|| slot.name("slot").is_some())
+						}),
+					} {
+						if invokable.name("hide").is_some() {
+							continue
+						}
+
+						let type_ = invokable.name("type").unwrap().as_str();
+						let name = invokable.name("name").unwrap().as_str();
+						let params_raw = invokable.name("params").unwrap().as_str();
+
+						let deprecated = match invokable.name("attr") {
+							Some(attr) => deprecated
+								.or(self.parse_deprecated_attr(attr.as_str(), ctx.module)?),
+							None => deprecated,
+						};
+
+						invokables.push(Invokable {
+							name,
+							ret: normalize_type(type_),
+							comment: decl.comment.map(|v| Comment::new(v, ctx.module)),
+							location: src.locate(decl.text),
+							deprecated,
+							params: self.parse_params(params_raw)?,
+							index: 0,
+							group: None,
+						});
+					} else if let Some(attached) = self.attached_fn_regex.captures(text)? {
+						// older classes only declare the attached object factory
+						attached_fn = Some(attached.name("type").unwrap().as_str());
+					} else if let Some(signal) =
+						self.signal_regex.captures(text)?.filter(|signal| {
+							// `Q_SIGNAL` declares a signal outside of a `signals:` section
+							decl.section == cpp::Section::Signals || signal.name("marker").is_some()
+						}) {
+						if signal.name("hide").is_some() {
+							continue
 						}
-						Ok::<_, anyhow::Error>(())
-					})()
-					.with_context(|| {
-						format!("while parsing macro `{}`", macro_.get(0).unwrap().as_str())
-					})?;
+
+						let name = signal.name("name").unwrap().as_str();
+						let params_raw = signal.name("params").unwrap().as_str();
+
+						signals.push(Signal {
+							name,
+							comment: decl.comment.map(|v| Comment::new(v, ctx.module)),
+							location: src.locate(decl.text),
+							deprecated,
+							params: self.parse_params(params_raw)?,
+							index: 0,
+							group: None,
+						});
+					} else if let (Some(enum_), Some(body)) =
+						(self.enum_regex.captures(text)?, decl.body)
+					{
+						let enum_name = enum_.name("enum_name").unwrap().as_str();
+						declared_enums.push((enum_name, decl.comment, body, decl.text));
+					} else if text
+						.split_whitespace()
+						.any(|word| word == "Q_INVOKABLE" || word == "Q_SLOT")
+					{
+						ctx.warnings.push(format!(
+							"{}: unable to parse invokable `{text}`, skipping",
+							src.locate(decl.text)
+						));
+					}
 				}
 
 				if let Some(prop) = default_property {
@@ -314,119 +593,154 @@ impl CppParser {
 					prop.default = true;
 				}
 
-				for invokable in self.fn_regex.captures_iter(body) {
-					let invokable = invokable?;
+				signals.retain(|signal: &Signal| !notify_signals.contains(&signal.name));
 
-					if invokable.name("hide").is_some() {
-						continue
-					}
-
-					let comment = invokable.name("comment").map(|m| m.as_str());
-					let type_ = invokable.name("type").unwrap().as_str();
-					let name = invokable.name("name").unwrap().as_str();
-					let params_raw = invokable.name("params").unwrap().as_str();
-
-					let mut params = Vec::new();
-
-					for param in self.fn_param_regex.captures_iter(params_raw) {
-						let param = param?;
-
-						let type_ = param.name("type").unwrap().as_str();
-						let name = param.name("name").unwrap().as_str();
+				Ok::<_, anyhow::Error>(())
+			})()
+			.with_context(|| format!("while parsing class `{name}` at {location}"))?;
 
-						params.push(InvokableParam { type_, name });
-					}
+			// qualified names are stored by their last segment, like class names are
+			let foreign = foreign.map(|f: &str| f.rsplit("::").next().unwrap());
+			let extension = extension.map(|e: &str| e.rsplit("::").next().unwrap());
 
-					invokables.push(Invokable {
-						name,
-						ret: type_,
-						comment: comment.map(|v| Comment::new(v, ctx.module)),
-						params,
-					});
+			if let Some(foreign) = foreign {
+				// QML_ELEMENT names the foreign type after itself
+				if !named && qml_name.is_some() {
+					qml_name = Some(foreign);
 				}
+			}
 
-				for signal_set in self.signals_regex.captures_iter(body) {
-					let signal_set = signal_set?;
-
-					let signals_body = signal_set.name("signals").unwrap().as_str();
+			// foreign registrations do not need to be QObjects themselves
+			let Some(type_) = classtype.or(foreign.map(|_| ClassType::Object)) else {
+				if qml_name.is_some() {
+					ctx.warnings.push(format!(
+						"{location}: class `{name}` is registered to QML but has no Q_OBJECT or Q_GADGET, skipping"
+					));
+				}
 
-					for signal in self.signal_regex.captures_iter(signals_body) {
-						let signal = signal?;
+				continue
+			};
 
-						if signal.name("invokable").is_some() {
-							continue;
-						}
+			let mut class = ClassInfo {
+				type_,
+				name: Cow::Borrowed(name),
+				qml_name: qml_name.map(Cow::Borrowed),
+				superclass: superclass.map(Cow::Borrowed),
+				singleton,
+				uncreatable: uncreatable && !force_creatable,
+				anonymous,
+				foreign,
+				extension,
+				comment: comment.map(|v| Comment::new(v, ctx.module)),
+				location,
+				deprecated: deprecated_class,
+				// qualified names are stored by their last segment, like class names are
+				attached: attached
+					.or(attached_fn)
+					.map(|a| a.rsplit("::").next().unwrap()),
+				properties,
+				invokables,
+				signals,
+				enums,
+			};
 
-						let comment = signal.name("comment").map(|m| m.as_str());
-						let name = signal.name("name").unwrap().as_str();
-						let params_raw = signal.name("params").unwrap().as_str();
+			class.order_members();
+			ctx.classes.push(class);
+		}
 
-						if notify_signals.contains(&name) {
-							continue;
-						}
+		Ok(())
+	}
 
-						let mut params = Vec::new();
+	/// Strips a leading `QSDOC_DEPRECATED` or `[[deprecated]]` from a declaration.
+	pub fn parse_deprecation<'a>(
+		&self,
+		text: &'a str,
+		module: &'a str,
+	) -> anyhow::Result<(Option<Deprecation<'a>>, &'a str)> {
+		if let Some(deprecated) = self.deprecated_regex.captures(text)? {
+			let replacement = deprecated
+				.name("replacement")
+				.map(|m| m.as_str().trim())
+				.filter(|r| !r.is_empty())
+				.map(|r| match r.starts_with("@@") {
+					true => Cow::Borrowed(r),
+					false => Cow::Owned(format!("@@{r}")),
+				});
 
-						for param in self.fn_param_regex.captures_iter(params_raw) {
-							let param = param?;
+			let rest = deprecated.name("rest").unwrap().as_str();
+			return Ok((
+				Some(Deprecation {
+					replacement,
+					module,
+				}),
+				rest,
+			))
+		}
 
-							let type_ = param.name("type").unwrap().as_str();
-							let name = param.name("name").unwrap().as_str();
+		if let Some(attribute) = self.attribute_regex.captures(text)? {
+			let attr = attribute.name("attr").unwrap().as_str();
 
-							params.push(InvokableParam { type_, name });
-						}
+			if let Some(deprecation) = self.parse_deprecated_attr(attr, module)? {
+				return Ok((Some(deprecation), attribute.name("rest").unwrap().as_str()))
+			}
+		}
 
-						signals.push(Signal {
-							name,
-							comment: comment.map(|v| Comment::new(v, ctx.module)),
-							params,
-						});
-					}
-				}
+		Ok((None, text))
+	}
 
-				for enum_ in self.enum_class_regex.captures_iter(body) {
-					let enum_ = enum_?;
+	/// Parses the contents of a `[[deprecated("...")]]` attribute, taking the first `@@` link
+	/// in its message as the replacement.
+	pub fn parse_deprecated_attr<'a>(
+		&self,
+		attr: &'a str,
+		module: &'a str,
+	) -> anyhow::Result<Option<Deprecation<'a>>> {
+		let Some(deprecated) = self.deprecated_attr_regex.captures(attr.trim())? else {
+			return Ok(None)
+		};
+
+		let replacement = deprecated
+			.name("message")
+			.and_then(|m| find_link(m.as_str()))
+			.map(Cow::Borrowed);
+
+		Ok(Some(Deprecation {
+			replacement,
+			module,
+		}))
+	}
 
-					let comment = enum_.name("comment").map(|m| m.as_str());
-					let enum_name = enum_.name("enum_name").unwrap().as_str();
-					let body = enum_.name("body").unwrap().as_str();
-					let variants = self.parse_enum_variants(body, ctx)?;
+	pub fn parse_params<'a>(&self, params_raw: &'a str) -> anyhow::Result<Vec<InvokableParam<'a>>> {
+		let mut params = Vec::new();
 
-					enums.push(EnumInfo {
-						namespace: name,
-						enum_name,
-						qml_name: enum_name,
-						comment: comment.map(|v| Comment::new(v, ctx.module)),
-						variants,
-					});
-				}
+		for param in split_top_level(params_raw, ',') {
+			let (param, default) = match split_top_level(param, '=')[..] {
+				[param, default] => (param, Some(default.trim())),
+				_ => (param, None),
+			};
 
-				Ok::<_, anyhow::Error>(())
-			})()
-			.with_context(|| format!("while parsing class `{name}`"))?;
+			// unnamed params cannot be documented
+			let Some(param) = self.fn_param_regex.captures(param.trim())? else { continue };
 
-			let Some(type_) = classtype else { continue };
+			let type_ = param.name("type").unwrap().as_str();
+			let name = param.name("name").unwrap().as_str();
 
-			ctx.classes.push(ClassInfo {
-				type_,
+			params.push(InvokableParam {
+				type_: normalize_type(type_),
 				name,
-				qml_name,
-				superclass: superclass.map(|s| Cow::Borrowed(s)),
-				singleton,
-				uncreatable: uncreatable && !force_creatable,
-				comment: comment.map(|v| Comment::new(v, ctx.module)),
-				properties,
-				invokables,
-				signals,
-				enums,
+				default,
 			});
 		}
 
-		Ok(())
+		Ok(params)
 	}
 
-	pub fn parse_enums<'a>(&self, text: &'a str, ctx: &mut ParseContext<'a>) -> anyhow::Result<()> {
-		for enum_ in self.enum_ns_regex.captures_iter(text) {
+	pub fn parse_enums<'a>(
+		&self,
+		src: Source<'a>,
+		ctx: &mut ParseContext<'a>,
+	) -> anyhow::Result<()> {
+		for enum_ in self.enum_ns_regex.captures_iter(src.text) {
 			let enum_ = enum_?;
 
 			let comment = enum_.name("comment").map(|m| m.as_str());
@@ -438,7 +752,7 @@ impl CppParser {
 				.unwrap_or(namespace);
 			let nsbody = enum_.name("nsbody").unwrap().as_str();
 			let body = enum_.name("body").unwrap().as_str();
-			let variants = self.parse_enum_variants(body, ctx)?;
+			let variants = self.parse_enum_variants(src, body, ctx)?;
 
 			for macro_ in self.macro_regex.captures_iter(nsbody) {
 				let macro_ = macro_?;
@@ -446,24 +760,14 @@ impl CppParser {
 				let type_ = macro_.name("type").unwrap().as_str();
 				let args = macro_.name("args").map(|m| m.as_str());
 
-				(|| {
-					match type_ {
-						"Q_DECLARE_FLAGS" => {
-							enum_name = args
-								.expect("Q_DECLARE_FLAGS must have arguments")
-								.split_once(',')
-								.expect("Q_DECLARE_FLAGS must have two arguments")
-								.0
-								.trim();
-						},
-						_ => {},
-					}
-
-					Ok::<_, anyhow::Error>(())
-				})()
-				.with_context(|| {
-					format!("while parsing macro `{}`", macro_.get(0).unwrap().as_str())
-				})?;
+				if type_ == "Q_DECLARE_FLAGS" {
+					enum_name = args
+						.expect("Q_DECLARE_FLAGS must have arguments")
+						.split_once(',')
+						.expect("Q_DECLARE_FLAGS must have two arguments")
+						.0
+						.trim();
+				}
 			}
 
 			ctx.enums.push(EnumInfo {
@@ -471,6 +775,8 @@ impl CppParser {
 				enum_name,
 				qml_name,
 				comment: comment.map(|v| Comment::new(v, ctx.module)),
+				location: src.locate(namespace),
+				deprecated: None,
 				variants,
 			});
 		}
@@ -480,6 +786,7 @@ impl CppParser {
 
 	pub fn parse_enum_variants<'a>(
 		&self,
+		src: Source<'a>,
 		body: &'a str,
 		ctx: &ParseContext<'a>,
 	) -> anyhow::Result<Vec<Variant<'a>>> {
@@ -494,31 +801,40 @@ impl CppParser {
 			variants.push(Variant {
 				name,
 				comment: comment.map(|v| Comment::new(v, ctx.module)),
+				location: src.locate(name),
 			});
 		}
 
 		Ok(variants)
 	}
 
-	pub fn parse<'a>(&self, text: &'a str, ctx: &mut ParseContext<'a>) -> anyhow::Result<()> {
-		self.parse_classes(text, ctx)?;
-		self.parse_enums(text, ctx)?;
+	pub fn parse<'a>(
+		&self,
+		filename: &'a str,
+		text: &'a str,
+		ctx: &mut ParseContext<'a>,
+	) -> anyhow::Result<()> {
+		let src = Source::new(filename, text);
+		self.parse_classes(src, ctx)?;
+		self.parse_enums(src, ctx)?;
 
 		Ok(())
 	}
 }
 
-// todo: use an actual parser (never)
 impl QmlParser {
 	pub fn new() -> Self {
 		Self {
-			class_regex: Regex::new(r#"((?<aliases>(\s*\/\/\/ alias.+)+)[\s\S]*?)?(?<comment>(\s*\/\/\/.*\n)+)?\s*(?<super>[A-Z]\w*)\s+{(?<body>[\s\S]*)}"#).unwrap(),
 			alias_regex: Regex::new(r#"alias\s+(?<alias>\w+)\s+(?<definition>[\w.]+)"#).unwrap(),
-			safe_body_regex: Regex::new(r#"((?<safebody1>^[\s\S]*?)(\n\s+[A-Z]\w* {[\s\S]*)|(?<safebody2>^[\s\S]*))"#).unwrap(),
-			// note: can pick up function bodies
-			property_regex: Regex::new(r#"(?<comment>(\s*\/\/\/.*\n)+)?\s*(?<default>default\s+)?(?<required>required\s+)?(?<readonly>readonly\s+)?property\s+(\/\*(?<typeoverride>\w+)\*\/)?(?<type>\w+)\s+(?<name>\w+)\s*(:\s*(?<definition>(.*{\n[\s\S]*?\/\/ END-DEF|.*?(?!{)\n)))?"#).unwrap(),
-			// note: can pick up prop bodies
-			function_regex: Regex::new(r#"(?<comment>(\s*\/\/\/.*\n)+)?\s*function\s+(?<name>\w+)\s*\((?<params>.*)\)\s*:\s*(?<return>\w+)\s*{"#).unwrap(),
+			function_param_regex: Regex::new(
+				r#"^\s*(?<name>\w+)\s*(:\s*(?<type>[\w.]+(\s*<[^=]*>)?))?\s*(=\s*(?<default>[\s\S]+?))?\s*$"#,
+			)
+			.unwrap(),
+			// accepts both `name: type` and the older `type name` syntax
+			signal_param_regex: Regex::new(
+				r#"^\s*((?<name>\w+)\s*:\s*(?<type>[\w.]+(\s*<.*>)?)|(?<oldtype>[\w.]+(\s*<.*>)?)\s+(?<oldname>\w+))\s*$"#,
+			)
+			.unwrap(),
 		}
 	}
 
@@ -528,90 +844,282 @@ impl QmlParser {
 		text: &'a str,
 		ctx: &mut ParseContext<'a>,
 	) -> anyhow::Result<()> {
-		for class in self.class_regex.captures_iter(text) {
-			let class = class?;
-
-			let mut aliases = HashMap::new();
-
-			if let Some(aliases_str) = class.name("aliases").map(|m| m.as_str()) {
-				for alias in self.alias_regex.captures_iter(aliases_str) {
-					let alias = alias?;
-					aliases.insert(
-						alias.name("alias").unwrap().as_str(),
-						alias.name("definition").unwrap().as_str(),
-					);
-				}
+		let src = Source::new(filename, text);
+		let document = qml::parse_document(src)?;
+
+		let mut aliases = HashMap::new();
+
+		for aliases_str in &document.aliases {
+			for alias in self.alias_regex.captures_iter(aliases_str) {
+				let alias = alias?;
+				aliases.insert(
+					alias.name("alias").unwrap().as_str(),
+					Cow::Borrowed(alias.name("definition").unwrap().as_str()),
+				);
 			}
+		}
 
-			let alias_lookup = |name: &'a str| *aliases.get(name).unwrap_or(&name);
-
-			let comment = class.name("comment").map(|m| m.as_str());
-			let superclass = class.name("super").unwrap().as_str();
-
-			let body = {
-				let body = class.name("body").unwrap().as_str();
-				let sbc = self
-					.safe_body_regex
-					.captures(body)?
-					.ok_or_else(|| anyhow!("unable to capture safebody"))?;
-				sbc.name("safebody1")
-					.or_else(|| sbc.name("safebody2"))
-					.unwrap()
-					.as_str()
-			};
+		let name = Path::new(filename)
+			.file_stem()
+			.and_then(|stem| stem.to_str())
+			.ok_or_else(|| anyhow!("invalid qml file name `{filename}`"))?;
+
+		// inline components are referred to by their bare name within the file
+		for component in &document.root.components {
+			aliases.insert(
+				component.name,
+				Cow::Owned(format!("{name}.{}", component.name)),
+			);
+		}
+		self.parse_object(src, Cow::Borrowed(name), &document.root, &aliases, ctx)?;
 
-			let mut properties = Vec::new();
+		// inline components are documented as their own types, which qml only finds qualified
+		// by the file declaring them, e.g. `Foo.Bar`
+		for component in &document.root.components {
+			let qualified = Cow::Owned(format!("{name}.{}", component.name));
 
-			for prop in self.property_regex.captures_iter(body) {
-				let prop = prop?;
-
-				let type_ = prop
-					.name("typeoverride")
-					.or_else(|| prop.name("type"))
-					.unwrap()
-					.as_str();
-
-				properties.push(Property {
-					type_: Cow::Owned(format!("QML:{}", alias_lookup(type_))),
-					name: prop.name("name").unwrap().as_str(),
-					comment: prop
-						.name("comment")
-						.map(|m| Comment::new(m.as_str(), ctx.module)),
-					readable: true,
-					writable: prop.name("readonly").is_none(),
-					default: prop.name("default").is_some(),
+			self.parse_object(src, qualified, &component.object, &aliases, ctx)
+				.with_context(|| format!("while parsing inline component `{}`", component.name))?;
+		}
+
+		Ok(())
+	}
+
+	fn parse_object<'a>(
+		&self,
+		src: Source<'a>,
+		name: Cow<'a, str>,
+		object: &qml::ObjectDef<'a>,
+		aliases: &HashMap<&'a str, Cow<'a, str>>,
+		ctx: &mut ParseContext<'a>,
+	) -> anyhow::Result<()> {
+		let mut properties = Vec::new();
+
+		for prop in &object.properties {
+			let type_ = prop.type_override.unwrap_or(prop.type_);
+
+			properties.push(Property {
+				type_: Cow::Owned(qml_type(type_, aliases)),
+				name: prop.name,
+				comment: prop.comment.map(|v| Comment::new(v, ctx.module)),
+				location: src.locate(prop.name),
+				deprecated: None,
+				readable: true,
+				writable: !prop.readonly,
+				default: prop.default,
+				// change signals of qml properties are implicit
+				notify: None,
+				index: 0,
+				group: None,
+			});
+		}
+
+		let mut invokables = Vec::new();
+
+		for func in &object.functions {
+			let name = func.name;
+
+			let mut params = Vec::new();
+
+			for param in split_top_level(func.params, ',') {
+				let param = self.function_param_regex.captures(param)?.ok_or_else(|| {
+					anyhow!("unable to parse param `{param}` of function `{name}`")
+				})?;
+
+				params.push(InvokableParam {
+					name: param.name("name").unwrap().as_str(),
+					// untyped params accept anything
+					type_: match param.name("type") {
+						Some(type_) => Cow::Owned(qml_type(type_.as_str(), aliases)),
+						None => Cow::Borrowed("QVariant"),
+					},
+					default: param.name("default").map(|m| m.as_str()),
 				});
 			}
 
-			let name = filename.split_once('.').unwrap().0;
+			invokables.push(Invokable {
+				name,
+				ret: match func.ret {
+					Some(ret) => Cow::Owned(qml_type(ret, aliases)),
+					None => Cow::Borrowed("void"),
+				},
+				comment: func.comment.map(|v| Comment::new(v, ctx.module)),
+				location: src.locate(func.name),
+				deprecated: None,
+				params,
+				index: 0,
+				group: None,
+			});
+		}
+
+		let mut signals = Vec::new();
+
+		for signal in &object.signals {
+			let name = signal.name;
+
+			// change signals are implicit for properties
+			if properties
+				.iter()
+				.any(|p| name.strip_suffix("Changed") == Some(p.name))
+			{
+				continue;
+			}
 
-			ctx.classes.push(ClassInfo {
-				type_: ClassType::Object,
+			let mut params = Vec::new();
+
+			for param in split_top_level(signal.params.unwrap_or(""), ',') {
+				let param = self
+					.signal_param_regex
+					.captures(param)?
+					.ok_or_else(|| anyhow!("unable to parse param `{param}` of signal `{name}`"))?;
+
+				let type_ = param
+					.name("type")
+					.or_else(|| param.name("oldtype"))
+					.unwrap();
+
+				params.push(InvokableParam {
+					name: param
+						.name("name")
+						.or_else(|| param.name("oldname"))
+						.unwrap()
+						.as_str(),
+					type_: Cow::Owned(qml_type(type_.as_str(), aliases)),
+					default: None,
+				});
+			}
+
+			signals.push(Signal {
 				name,
-				qml_name: Some(name),
-				superclass: Some(Cow::Owned(format!("QML:{}", superclass))),
-				singleton: false,
-				uncreatable: false,
-				comment: comment.map(|v| Comment::new(v, ctx.module)),
-				properties,
-				invokables: Vec::new(),
-				signals: Vec::new(),
-				enums: Vec::new(),
+				comment: signal.comment.map(|v| Comment::new(v, ctx.module)),
+				location: src.locate(signal.name),
+				deprecated: None,
+				params,
+				index: 0,
+				group: None,
 			});
 		}
 
+		let mut class = ClassInfo {
+			type_: ClassType::Object,
+			qml_name: Some(name.clone()),
+			name,
+			superclass: Some(Cow::Owned(qml_type(object.type_name, aliases))),
+			singleton: false,
+			uncreatable: false,
+			anonymous: false,
+			foreign: None,
+			extension: None,
+			comment: object.comment.map(|v| Comment::new(v, ctx.module)),
+			location: src.locate(object.type_name),
+			// qml files are deprecated with `@deprecated` comment tags
+			deprecated: None,
+			attached: None,
+			properties,
+			invokables,
+			signals,
+			enums: Vec::new(),
+		};
+
+		class.order_members();
+		ctx.classes.push(class);
+
 		Ok(())
 	}
 }
 
-impl ParseContext<'_> {
+/// Converts a QML type annotation such as `list<QtQuick.Item>` to a `QML:` type name.
+///
+/// Names are replaced by their `aliases`, and qualified names are kept, see `Index::qml_name`
+/// for how they are resolved.
+fn qml_type(type_: &str, aliases: &HashMap<&str, Cow<str>>) -> String {
+	let mut qml = String::from("QML:");
+	let mut name_start = None;
+
+	let push_name = |qml: &mut String, name: &str| {
+		qml.push_str(aliases.get(name).map(|alias| &**alias).unwrap_or(name));
+	};
+
+	for (i, c) in type_.char_indices() {
+		match c {
+			'<' | '>' | ',' => {
+				if let Some(start) = name_start.take() {
+					push_name(&mut qml, &type_[start..i]);
+				}
+
+				qml.push(c);
+
+				if c == ',' {
+					qml.push(' ');
+				}
+			},
+			_ if c.is_whitespace() => {
+				if let Some(start) = name_start.take() {
+					push_name(&mut qml, &type_[start..i]);
+				}
+			},
+			_ => {
+				name_start.get_or_insert(i);
+			},
+		}
+	}
+
+	if let Some(start) = name_start {
+		push_name(&mut qml, &type_[start..]);
+	}
+
+	qml
+}
+
+impl<'a> ParseContext<'a> {
+	/// Finds the class whose registration macros apply to `class`,
+	/// which is either a `QML_FOREIGN` wrapper or the class itself.
+	fn registration<'c>(&'c self, class: &'c ClassInfo<'a>) -> &'c ClassInfo<'a> {
+		self.classes
+			.iter()
+			.find(|wrapper| wrapper.foreign == Some(&*class.name))
+			.unwrap_or(class)
+	}
+
 	pub fn gen_typespec(&self, module: &str) -> typespec::TypeSpec {
 		typespec::TypeSpec {
 			typemap: self
 				.classes
 				.iter()
 				.flat_map(|class| {
-					let Some(qmlname) = class.qml_name else { return Vec::new() };
+					if let Some(foreign) = class.foreign {
+						// types from outside the module are registered without a module of their
+						// own and link externally, ones inside it are mapped with their class below
+						if self.classes.iter().any(|c| c.name == foreign) {
+							return Vec::new()
+						}
+
+						let Some(qmlname) = class
+							.qml_name
+							.as_deref()
+							.or(class.anonymous.then_some(foreign))
+						else {
+							return Vec::new()
+						};
+
+						return vec![typespec::QmlTypeMapping {
+							name: qmlname.to_string(),
+							cname: foreign.to_string(),
+							module: None,
+							anonymous: class.anonymous,
+						}]
+					}
+
+					let registration = self.registration(class);
+
+					// anonymous types are referred to by their c++ name
+					let Some(qmlname) = registration
+						.qml_name
+						.as_deref()
+						.or(registration.anonymous.then_some(&*class.name))
+					else {
+						return Vec::new()
+					};
 
 					let mut classes = Vec::new();
 					classes.push(typespec::QmlTypeMapping {
@@ -619,6 +1127,7 @@ impl ParseContext<'_> {
 						name: qmlname.to_string(),
 						cname: class.name.to_string(),
 						module: Some(module.to_string()),
+						anonymous: registration.anonymous,
 					});
 
 					// dirty hack to fix unknowns in resolution
@@ -628,6 +1137,7 @@ impl ParseContext<'_> {
 							name: qmlname.to_string(),
 							cname: format!("{}::{}", e.namespace, e.enum_name),
 							module: Some(module.to_string()),
+							anonymous: registration.anonymous,
 						});
 					}
 
@@ -638,10 +1148,16 @@ impl ParseContext<'_> {
 				.classes
 				.iter()
 				.filter_map(|class| {
-					let (description, details) = class
-						.comment
-						.map(parse_details_desc)
-						.unwrap_or((None, None));
+					// foreign registrations are merged into the type they register
+					if class.foreign.is_some() {
+						return None
+					}
+
+					let registration = self.registration(class);
+					let comment = class.comment.or(registration.comment);
+
+					let (description, details) =
+						comment.map(parse_details_desc).unwrap_or((None, None));
 
 					Some(typespec::Class {
 						name: class.name.to_string(),
@@ -650,11 +1166,30 @@ impl ParseContext<'_> {
 						details,
 						// filters gadgets
 						superclass: class.superclass.clone()?.to_string(),
-						singleton: class.singleton,
-						uncreatable: class.uncreatable,
-						properties: class.properties.iter().map(|p| p.clone().into()).collect(),
-						functions: class.invokables.iter().map(|f| f.as_typespec()).collect(),
-						signals: class.signals.iter().map(|s| s.as_typespec()).collect(),
+						singleton: registration.singleton,
+						uncreatable: registration.uncreatable,
+						source: Some(class.location.into()),
+						deprecated: deprecation(
+							&class.deprecated.clone().or(registration.deprecated.clone()),
+							comment,
+						),
+						attached: registration.attached.map(|a| a.to_string()),
+						extension: registration.extension.map(|e| e.to_string()),
+						properties: class
+							.properties
+							.iter()
+							.map(|p| p.as_typespec(self.nullable_pointers))
+							.collect(),
+						functions: class
+							.invokables
+							.iter()
+							.map(|f| f.as_typespec(self.nullable_pointers))
+							.collect(),
+						signals: class
+							.signals
+							.iter()
+							.map(|s| s.as_typespec(self.nullable_pointers))
+							.collect(),
 						enums: class
 							.enums
 							.iter()
@@ -674,6 +1209,8 @@ impl ParseContext<'_> {
 									description,
 									details,
 									varaints: enum_.variants.iter().map(|v| (*v).into()).collect(),
+									source: Some(enum_.location.into()),
+									deprecated: deprecation(&enum_.deprecated, enum_.comment),
 								}
 							})
 							.collect(),
@@ -684,9 +1221,13 @@ impl ParseContext<'_> {
 				.classes
 				.iter()
 				.filter_map(|class| match class.type_ {
-					ClassType::Gadget => Some(typespec::Gadget {
+					ClassType::Gadget if class.foreign.is_none() => Some(typespec::Gadget {
 						cname: class.name.to_string(),
-						properties: class.properties.iter().map(|p| p.clone().into()).collect(),
+						properties: class
+							.properties
+							.iter()
+							.map(|p| p.as_typespec(self.nullable_pointers))
+							.collect(),
 					}),
 					_ => None,
 				})
@@ -707,6 +1248,8 @@ impl ParseContext<'_> {
 						description,
 						details,
 						varaints: enum_.variants.iter().map(|v| (*v).into()).collect(),
+						source: Some(enum_.location.into()),
+						deprecated: deprecation(&enum_.deprecated, enum_.comment),
 					}
 				})
 				.collect(),
@@ -714,15 +1257,21 @@ impl ParseContext<'_> {
 	}
 }
 
-impl From<Property<'_>> for typespec::Property {
-	fn from(value: Property) -> Self {
-		Self {
-			type_: value.type_.to_string(),
-			name: value.name.to_string(),
-			details: value.comment.map(parse_details),
-			readable: value.readable,
-			writable: value.writable,
-			default: value.default,
+impl Property<'_> {
+	fn as_typespec(&self, nullable_pointers: bool) -> typespec::Property {
+		typespec::Property {
+			type_: self.type_.to_string(),
+			name: self.name.to_string(),
+			details: self.comment.map(parse_details),
+			readable: self.readable,
+			writable: self.writable,
+			default: self.default,
+			notify: self.notify.map(|n| n.to_string()),
+			nullable: nullability(self.comment, None, &self.type_, nullable_pointers),
+			index: self.index,
+			group: self.group.map(|g| g.to_string()),
+			source: Some(self.location.into()),
+			deprecated: deprecation(&self.deprecated, self.comment),
 		}
 	}
 }
@@ -732,40 +1281,204 @@ impl From<Variant<'_>> for typespec::Variant {
 		Self {
 			name: value.name.to_string(),
 			details: value.comment.map(parse_details),
+			source: Some(value.location.into()),
+			deprecated: deprecation(&None, value.comment),
 		}
 	}
 }
 
 impl Invokable<'_> {
-	fn as_typespec(&self) -> typespec::Function {
+	fn as_typespec(&self, nullable_pointers: bool) -> typespec::Function {
 		typespec::Function {
 			ret: self.ret.to_string(),
 			name: self.name.to_string(),
 			details: self.comment.map(parse_details),
-			params: self.params.iter().map(|p| (*p).into()).collect(),
+			params: self
+				.params
+				.iter()
+				.map(|p| p.as_typespec(self.comment, nullable_pointers))
+				.collect(),
+			nullable: nullability(self.comment, None, &self.ret, nullable_pointers),
+			index: self.index,
+			group: self.group.map(|g| g.to_string()),
+			source: Some(self.location.into()),
+			deprecated: deprecation(&self.deprecated, self.comment),
 		}
 	}
 }
 
 impl Signal<'_> {
-	fn as_typespec(&self) -> typespec::Signal {
+	fn as_typespec(&self, nullable_pointers: bool) -> typespec::Signal {
 		typespec::Signal {
 			name: self.name.to_string(),
 			details: self.comment.map(parse_details),
-			params: self.params.iter().map(|p| (*p).into()).collect(),
+			params: self
+				.params
+				.iter()
+				.map(|p| p.as_typespec(self.comment, nullable_pointers))
+				.collect(),
+			index: self.index,
+			group: self.group.map(|g| g.to_string()),
+			source: Some(self.location.into()),
+			deprecated: deprecation(&self.deprecated, self.comment),
+		}
+	}
+}
+
+impl InvokableParam<'_> {
+	/// `comment` is the comment of the function or signal taking the parameter.
+	fn as_typespec(&self, comment: Option<Comment>, nullable_pointers: bool) -> typespec::FnParam {
+		typespec::FnParam {
+			type_: self.type_.to_string(),
+			name: self.name.to_string(),
+			default: self.default.map(|v| v.to_string()),
+			nullable: nullability(comment, Some(self.name), &self.type_, nullable_pointers),
 		}
 	}
 }
 
-impl From<InvokableParam<'_>> for typespec::FnParam {
-	fn from(value: InvokableParam<'_>) -> Self {
+/// Splits `text` on `separator` where it is not nested in brackets or string literals,
+/// skipping empty parts.
+pub fn split_top_level(text: &str, separator: char) -> Vec<&str> {
+	let mut parts = Vec::new();
+	let mut depth = 0usize;
+	let mut quote = None;
+	let mut escaped = false;
+	let mut start = 0;
+
+	for (i, c) in text.char_indices() {
+		if let Some(q) = quote {
+			match c {
+				_ if escaped => escaped = false,
+				'\\' => escaped = true,
+				_ if c == q => quote = None,
+				_ => {},
+			}
+
+			continue
+		}
+
+		match c {
+			'"' | '\'' | '`' => quote = Some(c),
+			'(' | '[' | '{' | '<' => depth += 1,
+			')' | ']' | '}' | '>' => depth = depth.saturating_sub(1),
+			_ if c == separator && depth == 0 => {
+				parts.push(&text[start..i]);
+				start = i + c.len_utf8();
+			},
+			_ => {},
+		}
+	}
+
+	parts.push(&text[start..]);
+	parts.retain(|p| !p.trim().is_empty());
+	parts
+}
+
+/// Collapses whitespace in a c++ type, e.g. `QMap< QString,QVariant > *` to `QMap<QString, QVariant>*`.
+fn normalize_type(type_: &str) -> Cow<'_, str> {
+	if !type_.contains(char::is_whitespace) && !type_.contains(',') {
+		return Cow::Borrowed(type_)
+	}
+
+	let type_ = type_
+		.split_whitespace()
+		.collect::<Vec<_>>()
+		.join(" ")
+		.replace(" *", "*")
+		.replace("< ", "<")
+		.replace(" >", ">")
+		.replace(" ,", ",")
+		.replace(", ", ",")
+		.replace(',', ", ");
+
+	Cow::Owned(type_)
+}
+
+impl From<Location<'_>> for typespec::SourceLocation {
+	fn from(value: Location<'_>) -> Self {
 		Self {
-			type_: value.type_.to_string(),
-			name: value.name.to_string(),
+			file: value.file.to_string(),
+			line: value.line,
 		}
 	}
 }
 
+impl From<Deprecation<'_>> for typespec::Deprecation {
+	fn from(value: Deprecation<'_>) -> Self {
+		let replacement = value.replacement.map(|replacement| {
+			let mut replacement = replacement.to_string();
+			let reformat_ctx = reformat::Context {
+				module: value.module,
+			};
+
+			crate::reformat::TypeLinks.reformat(&reformat_ctx, &mut replacement);
+			replacement.trim_end().to_string()
+		});
+
+		Self { replacement }
+	}
+}
+
+/// Combines a deprecation from a macro or attribute with a `/// @deprecated` comment tag.
+fn deprecation(
+	deprecated: &Option<Deprecation>,
+	comment: Option<Comment>,
+) -> Option<typespec::Deprecation> {
+	deprecated
+		.clone()
+		.or_else(|| {
+			comment?.text.lines().find_map(|line| {
+				let line = line.trim().trim_start_matches('/').trim_start();
+				let rest = line.strip_prefix("@deprecated")?;
+
+				Some(Deprecation {
+					replacement: find_link(rest).map(Cow::Borrowed),
+					module: comment?.module,
+				})
+			})
+		})
+		.map(Into::into)
+}
+
+/// Finds whether a value may be null from `@nullable` and `@nonnull` comment tags.
+///
+/// A bare tag applies to a property or return value, and a tag followed by a name applies to
+/// the parameter of that name. Untagged c++ pointers fall back to `nullable_pointers`.
+fn nullability(
+	comment: Option<Comment>,
+	param: Option<&str>,
+	type_: &str,
+	nullable_pointers: bool,
+) -> bool {
+	let tagged = comment.and_then(|comment| {
+		comment.text.lines().find_map(|line| {
+			let line = line.trim().trim_start_matches('/').trim_start();
+			let mut words = line.split_whitespace();
+
+			let nullable = match words.next()? {
+				"@nullable" => true,
+				"@nonnull" => false,
+				_ => return None,
+			};
+
+			(words.next() == param).then_some(nullable)
+		})
+	});
+
+	tagged.unwrap_or_else(|| nullable_pointers && type_.ends_with('*'))
+}
+
+/// Finds the first `@@` link in a piece of text.
+fn find_link(text: &str) -> Option<&str> {
+	let start = text.find("@@")?;
+	let link = text[start..].split_whitespace().next()?;
+	let link = link.trim_end_matches(['.', ',', ';']);
+
+	// a bare `@@` links nowhere
+	(link.len() > 2).then_some(link)
+}
+
 fn parse_details(comment: Comment) -> String {
 	let mut seen_content = false;
 
@@ -778,13 +1491,20 @@ fn parse_details(comment: Comment) -> String {
 				.map(|line| line.strip_prefix(' ').unwrap_or(line))
 				.unwrap_or(line)
 		})
+		// tags are read separately, see `deprecation`, `nullability` and `order_members`
+		.filter(|line| {
+			!(line.starts_with("@deprecated")
+				|| line.starts_with("@nullable")
+				|| line.starts_with("@nonnull")
+				|| line.starts_with("@group"))
+		})
 		.filter(|line| {
 			let any = !line.is_empty();
 			let filter = any || seen_content;
 			seen_content |= any;
 			filter
 		})
-		.fold(String::new(), |accum, line| accum + line.as_ref() + "\n");
+		.fold(String::new(), |accum, line| accum + line + "\n");
 
 	let reformat_ctx = reformat::Context {
 		module: comment.module,
@@ -796,21 +1516,16 @@ fn parse_details(comment: Comment) -> String {
 	str
 }
 
-fn parse_details_desc(comment: Comment) -> (Option<String>, Option<String>) {
+pub fn parse_details_desc(comment: Comment) -> (Option<String>, Option<String>) {
 	let details = parse_details(comment);
-	if details.starts_with('!') {
-		match details[1..].split_once('\n') {
+	if let Some(rest) = details.strip_prefix('!') {
+		match rest.split_once('\n') {
 			Some((desc, details)) => (
 				Some(desc.strip_prefix(' ').unwrap_or(desc).to_string()),
 				Some(details.to_string()),
 			),
 			None => (
-				Some(
-					details[1..]
-						.strip_prefix(' ')
-						.unwrap_or(&details[1..])
-						.to_string(),
-				),
+				Some(rest.strip_prefix(' ').unwrap_or(rest).to_string()),
 				None,
 			),
 		}
@@ -818,3 +1533,76 @@ fn parse_details_desc(comment: Comment) -> (Option<String>, Option<String>) {
 		(None, Some(details))
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn invokable_return_types() {
+		let mut ctx = ParseContext::new("Test");
+		CppParser::new()
+			.parse(
+				"test.hpp",
+				"class Foo: public QObject {
+					Q_OBJECT;
+					QML_ELEMENT;
+				public:
+					Q_INVOKABLE QMap<QString,QVariant> map();
+					Q_INVOKABLE QPair<int, int> pair(int a, int b = 3);
+					Q_INVOKABLE const QString& ref() const;
+					Q_INVOKABLE virtual void virt();
+					Q_INVOKABLE QObject *ptr();
+					Q_INVOKABLE std::function<void()> callback();
+				public slots:
+					QList< QString > slot();
+				};",
+				&mut ctx,
+			)
+			.unwrap();
+
+		let functions = ctx.classes[0]
+			.invokables
+			.iter()
+			.map(|f| (f.name, &*f.ret))
+			.collect::<Vec<_>>();
+
+		assert_eq!(functions, [
+			("map", "QMap<QString, QVariant>"),
+			("pair", "QPair<int, int>"),
+			("ref", "QString"),
+			("virt", "void"),
+			("ptr", "QObject*"),
+			("slot", "QList<QString>"),
+		]);
+
+		assert_eq!(ctx.warnings.len(), 1);
+		assert!(ctx.warnings[0].contains("callback"), "{}", ctx.warnings[0]);
+	}
+
+	#[test]
+	fn private_invokables() {
+		let mut ctx = ParseContext::new("Test");
+		CppParser::new()
+			.parse(
+				"test.hpp",
+				"class Foo: public QObject {
+					Q_OBJECT;
+					QML_ELEMENT;
+					Q_INVOKABLE void privateFn();
+				private slots:
+					void privateSlot();
+				};",
+				&mut ctx,
+			)
+			.unwrap();
+
+		// Q_INVOKABLE exposes methods regardless of access, slots only when public
+		let functions = ctx.classes[0]
+			.invokables
+			.iter()
+			.map(|f| f.name)
+			.collect::<Vec<_>>();
+		assert_eq!(functions, ["privateFn"]);
+	}
+}
@@ -0,0 +1,605 @@
+use anyhow::{bail, Context};
+
+use crate::{
+	location::Source,
+	tokenizer::{self, matching_close, Lang, Token, TokenKind},
+};
+
+#[derive(Debug)]
+pub struct Document<'a> {
+	/// `/// alias` comment lines preceding the root object
+	pub aliases: Vec<&'a str>,
+	pub root: ObjectDef<'a>,
+}
+
+#[derive(Debug)]
+pub struct ObjectDef<'a> {
+	pub type_name: &'a str,
+	pub comment: Option<&'a str>,
+	pub properties: Vec<PropertyDef<'a>>,
+	pub functions: Vec<FunctionDef<'a>>,
+	pub signals: Vec<SignalDef<'a>>,
+	/// `component Name: Base { ... }` declarations
+	pub components: Vec<ComponentDef<'a>>,
+}
+
+#[derive(Debug)]
+pub struct ComponentDef<'a> {
+	pub name: &'a str,
+	pub object: ObjectDef<'a>,
+}
+
+#[derive(Debug)]
+pub struct PropertyDef<'a> {
+	pub comment: Option<&'a str>,
+	pub name: &'a str,
+	pub type_: &'a str,
+	/// type given in a `/*Type*/` comment before the real type
+	pub type_override: Option<&'a str>,
+	pub default: bool,
+	pub readonly: bool,
+}
+
+#[derive(Debug)]
+pub struct FunctionDef<'a> {
+	pub comment: Option<&'a str>,
+	pub name: &'a str,
+	pub params: &'a str,
+	pub ret: Option<&'a str>,
+}
+
+#[derive(Debug)]
+pub struct SignalDef<'a> {
+	pub comment: Option<&'a str>,
+	pub name: &'a str,
+	pub params: Option<&'a str>,
+}
+
+pub fn parse_document<'a>(src: Source<'a>) -> anyhow::Result<Document<'a>> {
+	let text = src.text;
+	let tokens = tokenizer::tokenize(src, Lang::Qml)?;
+	let mut aliases = Vec::new();
+	let mut comment = None::<(usize, usize)>;
+	let mut i = 0;
+
+	while i < tokens.len() {
+		let token = &tokens[i];
+
+		match token.kind {
+			TokenKind::DocComment => {
+				if token
+					.text
+					.trim_start_matches('/')
+					.trim_start()
+					.starts_with("alias ")
+				{
+					aliases.push(token.text);
+				} else {
+					comment = Some(match comment {
+						Some((start, _)) => (start, token.end()),
+						None => (token.start, token.end()),
+					});
+				}
+
+				i += 1;
+				continue
+			},
+			TokenKind::Comment => {
+				comment = None;
+				i += 1;
+				continue
+			},
+			_ => {},
+		}
+
+		if token.is_ident("import") || token.is_ident("pragma") {
+			comment = None;
+			i = skip_line(text, &tokens, i);
+			continue
+		}
+
+		if token.kind != TokenKind::Ident {
+			bail!("unexpected `{}` at {}", token.text, src.at(token.start));
+		}
+
+		let open = qualified_name_end(&tokens, i);
+
+		if !tokens.get(open).map(|t| t.is_punct("{")).unwrap_or(false) {
+			bail!("expected root object at {}", src.at(token.start));
+		}
+
+		let close = matching_close(src, &tokens, open)
+			.with_context(|| format!("unterminated root object at {}", src.at(token.start)))?;
+
+		let root = parse_object(
+			src,
+			&tokens[open + 1..close],
+			&text[token.start..tokens[open - 1].end()],
+			comment.map(|(start, end)| &text[start..end]),
+		)?;
+
+		return Ok(Document { aliases, root })
+	}
+
+	bail!("no root object found")
+}
+
+fn parse_object<'a>(
+	src: Source<'a>,
+	tokens: &[Token<'a>],
+	type_name: &'a str,
+	comment: Option<&'a str>,
+) -> anyhow::Result<ObjectDef<'a>> {
+	let text = src.text;
+	let mut object = ObjectDef {
+		type_name,
+		comment,
+		properties: Vec::new(),
+		functions: Vec::new(),
+		signals: Vec::new(),
+		components: Vec::new(),
+	};
+
+	let mut comment = None::<(usize, usize)>;
+	let mut i = 0;
+
+	while i < tokens.len() {
+		let token = &tokens[i];
+
+		match token.kind {
+			TokenKind::DocComment => {
+				comment = Some(match comment {
+					Some((start, _)) => (start, token.end()),
+					None => (token.start, token.end()),
+				});
+
+				i += 1;
+				continue
+			},
+			TokenKind::Comment => {
+				i += 1;
+				continue
+			},
+			_ => {},
+		}
+
+		let this_comment = comment.take().map(|(start, end)| &text[start..end]);
+
+		// members may only start at the beginning of a statement
+		let statement_start = i == 0 || {
+			let prev = &tokens[i - 1];
+			prev.is_punct(";") || prev.is_punct("}") || text[prev.end()..token.start].contains('\n')
+		};
+
+		let member = (|| {
+			if !statement_start || token.kind != TokenKind::Ident {
+				return Ok(None)
+			}
+
+			let mut j = i;
+			let mut default = false;
+			let mut readonly = false;
+
+			loop {
+				match tokens.get(j).map(|t| t.text) {
+					Some("default") => default = true,
+					Some("required") => {},
+					Some("readonly") => readonly = true,
+					_ => break,
+				}
+
+				j += 1;
+			}
+
+			let is_keyword = |j: usize| {
+				tokens
+					.get(j + 1)
+					.map(|t| t.kind == TokenKind::Ident || t.kind == TokenKind::Comment)
+					.unwrap_or(false)
+			};
+
+			match tokens.get(j).map(|t| t.text) {
+				Some("property") if is_keyword(j) => {
+					let (prop, end) = parse_property(text, tokens, j + 1)?;
+
+					object.properties.push(PropertyDef {
+						comment: this_comment,
+						default,
+						readonly,
+						..prop
+					});
+
+					Ok(Some(end))
+				},
+				Some("function") if j == i && is_keyword(j) => {
+					let name = tokens[j + 1].text;
+
+					let open = j + 2;
+					if !tokens.get(open).map(|t| t.is_punct("(")).unwrap_or(false) {
+						bail!("expected parameter list for function `{name}`");
+					}
+
+					let close =
+						matching_close(src, tokens, open).context("unterminated parameter list")?;
+
+					let mut body = close + 1;
+					let mut ret = None;
+
+					if tokens.get(body).map(|t| t.is_punct(":")).unwrap_or(false) {
+						let ret_start = body + 1;
+						body = type_end(tokens, ret_start);
+
+						if body > ret_start {
+							ret = Some(&text[tokens[ret_start].start..tokens[body - 1].end()]);
+						}
+					}
+
+					if !tokens.get(body).map(|t| t.is_punct("{")).unwrap_or(false) {
+						bail!("expected body for function `{name}`");
+					}
+
+					let end = matching_close(src, tokens, body)
+						.with_context(|| format!("unterminated body of function `{name}`"))?;
+
+					object.functions.push(FunctionDef {
+						comment: this_comment,
+						name,
+						params: &text[tokens[open].end()..tokens[close].start],
+						ret,
+					});
+
+					Ok(Some(end + 1))
+				},
+				Some("signal") if j == i && is_keyword(j) => {
+					let name = tokens[j + 1].text;
+					let mut end = j + 2;
+					let mut params = None;
+
+					if tokens.get(end).map(|t| t.is_punct("(")).unwrap_or(false) {
+						let close = matching_close(src, tokens, end)
+							.context("unterminated parameter list")?;
+
+						params = Some(&text[tokens[end].end()..tokens[close].start]);
+						end = close + 1;
+					}
+
+					object.signals.push(SignalDef {
+						comment: this_comment,
+						name,
+						params,
+					});
+
+					Ok(Some(end))
+				},
+				Some("component") if j == i && is_keyword(j) => {
+					let name = tokens[j + 1].text;
+
+					if !tokens.get(j + 2).map(|t| t.is_punct(":")).unwrap_or(false) {
+						bail!("expected base type for component `{name}`");
+					}
+
+					let base = j + 3;
+					let open = qualified_name_end(tokens, base);
+
+					if open == base || !tokens.get(open).map(|t| t.is_punct("{")).unwrap_or(false) {
+						bail!("expected body for component `{name}`");
+					}
+
+					let close = matching_close(src, tokens, open)
+						.with_context(|| format!("unterminated body of component `{name}`"))?;
+
+					let inner = parse_object(
+						src,
+						&tokens[open + 1..close],
+						&text[tokens[base].start..tokens[open - 1].end()],
+						this_comment,
+					)
+					.with_context(|| format!("while parsing inline component `{name}`"))?;
+
+					object.components.push(ComponentDef {
+						name,
+						object: inner,
+					});
+
+					Ok(Some(close + 1))
+				},
+				_ => Ok(None),
+			}
+		})()
+		.with_context(|| format!("while parsing member at {}", src.at(token.start)))?;
+
+		if let Some(end) = member {
+			i = end;
+			continue
+		}
+
+		// bindings, child objects and anything else are skipped, including their bodies
+		if token.kind == TokenKind::Punct && matches!(token.text, "(" | "[" | "{") {
+			let close = matching_close(src, tokens, i)
+				.with_context(|| format!("unbalanced brackets at {}", src.at(token.start)))?;
+
+			// inline components may be declared in child objects, e.g. `Item { component A: ... }`
+			let child_object = token.text == "{"
+				&& i > 0 && tokens[i - 1].kind == TokenKind::Ident
+				&& tokens[i - 1].text.starts_with(char::is_uppercase);
+
+			if child_object {
+				let child = parse_object(src, &tokens[i + 1..close], tokens[i - 1].text, None)
+					.with_context(|| {
+						format!("while parsing child object at {}", src.at(token.start))
+					})?;
+
+				object.components.extend(child.components);
+			}
+
+			i = close;
+		}
+
+		i += 1;
+	}
+
+	Ok(object)
+}
+
+/// Parses `type name` following `property`, returning the index after the declaration.
+fn parse_property<'a>(
+	text: &'a str,
+	tokens: &[Token<'a>],
+	mut i: usize,
+) -> anyhow::Result<(PropertyDef<'a>, usize)> {
+	let mut type_override = None;
+
+	if let Some(token) = tokens.get(i).filter(|t| t.kind == TokenKind::Comment) {
+		type_override = token
+			.text
+			.strip_prefix("/*")
+			.and_then(|t| t.strip_suffix("*/"))
+			.map(|t| t.trim());
+		i += 1;
+	}
+
+	let type_start = i;
+	let type_end = type_end(tokens, type_start);
+
+	if type_end == type_start {
+		bail!("expected property type");
+	}
+
+	let Some(name) = tokens.get(type_end).filter(|t| t.kind == TokenKind::Ident) else {
+		bail!("expected property name");
+	};
+
+	let prop = PropertyDef {
+		comment: None,
+		name: name.text,
+		type_: &text[tokens[type_start].start..tokens[type_end - 1].end()],
+		type_override,
+		default: false,
+		readonly: false,
+	};
+
+	Ok((prop, type_end + 1))
+}
+
+/// Finds the end of a type such as `int`, `QtQuick.Item` or `list<Item>`.
+fn type_end(tokens: &[Token], i: usize) -> usize {
+	let mut i = qualified_name_end(tokens, i);
+
+	if tokens.get(i).map(|t| t.is_punct("<")).unwrap_or(false) {
+		let mut depth = 0usize;
+
+		while let Some(token) = tokens.get(i) {
+			match token.text {
+				"<" => depth += 1,
+				">" => depth -= 1,
+				_ => {},
+			}
+
+			i += 1;
+
+			if depth == 0 {
+				break
+			}
+		}
+	}
+
+	i
+}
+
+/// Finds the end of a possibly qualified name such as `QtQuick.Item`.
+fn qualified_name_end(tokens: &[Token], mut i: usize) -> usize {
+	while tokens
+		.get(i)
+		.map(|t| t.kind == TokenKind::Ident)
+		.unwrap_or(false)
+	{
+		i += 1;
+
+		if !tokens.get(i).map(|t| t.is_punct(".")).unwrap_or(false) {
+			break
+		}
+
+		i += 1;
+	}
+
+	i
+}
+
+fn skip_line(text: &str, tokens: &[Token], mut i: usize) -> usize {
+	let line_end = text[tokens[i].start..]
+		.find('\n')
+		.map(|end| tokens[i].start + end)
+		.unwrap_or(text.len());
+
+	while tokens.get(i).map(|t| t.start < line_end).unwrap_or(false) {
+		i += 1;
+	}
+
+	i
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::parse::{InvokableParam, ParseContext, QmlParser};
+
+	fn params<'a>(params: &[InvokableParam<'a>]) -> Vec<(&'a str, String, Option<&'a str>)> {
+		params
+			.iter()
+			.map(|p| (p.name, p.type_.to_string(), p.default))
+			.collect()
+	}
+
+	#[test]
+	fn nested_objects() {
+		let document = parse_document(Source::new(
+			"Foo.qml",
+			"Item {
+				/// Shown.
+				property int shown
+				Rectangle {
+					property int hidden: 5
+					function hiddenFn() {}
+					signal hiddenSignal()
+					Text { property string deeper }
+				}
+				anchors { fill: parent }
+				function shownFn() {}
+			}",
+		))
+		.unwrap();
+
+		let root = &document.root;
+		assert_eq!(root.type_name, "Item");
+
+		let properties = root.properties.iter().map(|p| p.name).collect::<Vec<_>>();
+		assert_eq!(properties, ["shown"]);
+
+		let functions = root.functions.iter().map(|f| f.name).collect::<Vec<_>>();
+		assert_eq!(functions, ["shownFn"]);
+		assert!(root.signals.is_empty());
+	}
+
+	#[test]
+	fn inline_components() {
+		let document = parse_document(Source::new(
+			"Foo.qml",
+			"Item {
+				/// An entry.
+				component Entry: Rectangle {
+					property string label
+				}
+				Column {
+					Repeater {
+						component Row: Item {}
+					}
+				}
+			}",
+		))
+		.unwrap();
+
+		let components = document
+			.root
+			.components
+			.iter()
+			.map(|c| c.name)
+			.collect::<Vec<_>>();
+		assert_eq!(components, ["Entry", "Row"]);
+
+		let entry = &document.root.components[0].object;
+		assert_eq!(entry.type_name, "Rectangle");
+		assert_eq!(entry.comment, Some("/// An entry."));
+		assert_eq!(entry.properties[0].name, "label");
+	}
+
+	#[test]
+	fn registered_components() {
+		let mut ctx = ParseContext::new("Test");
+		QmlParser::new()
+			.parse(
+				"Foo.qml",
+				"Item {
+					property Entry entry
+					component Entry: Rectangle {}
+					component Special: Entry {}
+				}",
+				&mut ctx,
+			)
+			.unwrap();
+
+		let classes = ctx
+			.classes
+			.iter()
+			.map(|c| (&*c.name, c.superclass.as_deref().unwrap()))
+			.collect::<Vec<_>>();
+
+		assert_eq!(classes, [
+			("Foo", "QML:Item"),
+			("Foo.Entry", "QML:Rectangle"),
+			("Foo.Special", "QML:Foo.Entry"),
+		]);
+		assert_eq!(ctx.classes[0].properties[0].type_, "QML:Foo.Entry");
+	}
+
+	#[test]
+	fn typed_and_default_params() {
+		let mut ctx = ParseContext::new("Test");
+		QmlParser::new()
+			.parse(
+				"Foo.qml",
+				"Item {
+					function typed(a: int, b: list<Item>, c: QtQuick.Item, d: var = [1, 2]): string {}
+					function untyped(a, b = { x: 1 }) {}
+					signal modern(a: int, b: list<var>)
+					signal old(int a, QtQuick.Item b)
+				}",
+				&mut ctx,
+			)
+			.unwrap();
+
+		let class = &ctx.classes[0];
+		assert_eq!(params(&class.invokables[0].params), [
+			("a", "QML:int".to_string(), None),
+			("b", "QML:list<Item>".to_string(), None),
+			("c", "QML:QtQuick.Item".to_string(), None),
+			("d", "QML:var".to_string(), Some("[1, 2]")),
+		]);
+		assert_eq!(class.invokables[0].ret, "QML:string");
+		// untyped params accept anything
+		assert_eq!(params(&class.invokables[1].params), [
+			("a", "QVariant".to_string(), None),
+			("b", "QVariant".to_string(), Some("{ x: 1 }")),
+		]);
+
+		assert_eq!(params(&class.signals[0].params), [
+			("a", "QML:int".to_string(), None),
+			("b", "QML:list<var>".to_string(), None),
+		]);
+		assert_eq!(params(&class.signals[1].params), [
+			("a", "QML:int".to_string(), None),
+			("b", "QML:QtQuick.Item".to_string(), None),
+		]);
+	}
+
+	#[test]
+	fn regex_literals() {
+		let document = parse_document(Source::new(
+			"Foo.qml",
+			r#"Item {
+				property real half: width / 2 / 1
+				function re(s: string): bool { return /"/.test(s); }
+				function classes(s: string): bool { return s.match(/[/'`]+\//g) !== null; }
+				/// Shown.
+				property string after: "a"
+			}"#,
+		))
+		.unwrap();
+
+		let root = &document.root;
+		let functions = root.functions.iter().map(|f| f.name).collect::<Vec<_>>();
+		assert_eq!(functions, ["re", "classes"]);
+
+		let properties = root.properties.iter().map(|p| p.name).collect::<Vec<_>>();
+		assert_eq!(properties, ["half", "after"]);
+	}
+}
@@ -0,0 +1,554 @@
+use std::{collections::HashSet, fmt::Write, path::Path};
+
+use crate::typespec::{Class, Enum, FnParam, Gadget, Property, TypeSpec};
+
+/// Generates a `.qmltypes` file describing every c++ type defined by `module`,
+/// along with a `qmldir` file pointing at it as `typeinfo` and listing its qml files.
+///
+/// Types are exported with `version`, as `(major, minor)`.
+pub fn gen_qmltypes(
+	module: &str,
+	version: (u32, u32),
+	typeinfo: &str,
+	typespec: &TypeSpec,
+) -> (String, String) {
+	let mut w = Writer::new();
+
+	w.line("import QtQuick.tooling 1.2");
+	w.line("");
+	w.line("// This file was generated by typegen. Do not edit.");
+	w.line("");
+	w.open("Module");
+
+	// types defined by qml files are listed in qmldir instead, where tooling reads the file itself
+	let (qml_files, classes) = typespec
+		.classes
+		.iter()
+		.filter(|c| c.module == module)
+		.partition::<Vec<_>, _>(|c| c.source.as_ref().is_some_and(|s| s.file.ends_with(".qml")));
+
+	for class in &classes {
+		write_class(&mut w, module, version, class, typespec);
+	}
+
+	// gadgets are not tagged with a module, so only emit the ones our classes use, anywhere in
+	// their members or through other gadgets
+	let mut used = classes
+		.iter()
+		.flat_map(|c| member_types(c))
+		.flat_map(|type_| referenced_types(type_, typespec))
+		.collect::<HashSet<_>>();
+
+	let mut gadgets = HashSet::new();
+	while let Some(gadget) = typespec
+		.gadgets
+		.iter()
+		.find(|g| used.contains(&g.cname) && !gadgets.contains(&g.cname))
+	{
+		gadgets.insert(&gadget.cname);
+		used.extend(
+			gadget
+				.properties
+				.iter()
+				.flat_map(|p| referenced_types(&p.type_, typespec)),
+		);
+	}
+
+	for gadget in typespec
+		.gadgets
+		.iter()
+		.filter(|g| gadgets.contains(&g.cname))
+	{
+		write_gadget(&mut w, gadget, typespec);
+	}
+
+	for enum_ in typespec
+		.enums
+		.iter()
+		.filter(|e| e.module.as_ref().map(|v| v as &str) == Some(module))
+	{
+		write_namespace_enum(&mut w, module, version, enum_);
+	}
+
+	w.close();
+
+	let mut qmldir = format!("module {module}\ntypeinfo {typeinfo}\n");
+
+	for class in qml_files {
+		let file = class.source.as_ref().unwrap();
+		let file = Path::new(&file.file).file_name().unwrap().to_string_lossy();
+
+		// inline components are declared by the file containing them
+		if let Some(qml_name) = qml_name(module, class, typespec).filter(|n| !n.contains('.')) {
+			writeln!(qmldir, "{qml_name} {}.{} {file}", version.0, version.1).unwrap();
+		}
+	}
+
+	let mut depends = w
+		.referenced
+		.iter()
+		.filter_map(|cname| typespec.typemap.iter().find(|t| &t.cname == cname))
+		.filter_map(|t| t.module.as_ref())
+		.filter(|m| *m != module)
+		.map(|m| m.strip_prefix("qml.").unwrap_or(m))
+		.collect::<Vec<_>>();
+
+	depends.sort();
+	depends.dedup();
+
+	for depend in depends {
+		writeln!(qmldir, "depends {depend}").unwrap();
+	}
+
+	(w.text, qmldir)
+}
+
+/// Types of every property, parameter and return value of `class`.
+fn member_types(class: &Class) -> impl Iterator<Item = &String> {
+	let functions = class
+		.functions
+		.iter()
+		.flat_map(|f| std::iter::once(&f.ret).chain(f.params.iter().map(|p| &p.type_)));
+	let signals = class
+		.signals
+		.iter()
+		.flat_map(|s| s.params.iter().map(|p| &p.type_));
+
+	class
+		.properties
+		.iter()
+		.map(|p| &p.type_)
+		.chain(functions)
+		.chain(signals)
+}
+
+/// c++ names of every type `type_` is made of, including generic arguments such as list
+/// elements.
+fn referenced_types(type_: &str, typespec: &TypeSpec) -> Vec<String> {
+	let (qml, type_) = match type_.strip_prefix("QML:") {
+		Some(type_) => (true, type_),
+		None => (false, type_),
+	};
+
+	type_
+		.split(|c: char| !(c.is_alphanumeric() || c == '_' || c == ':'))
+		.filter(|name| !name.is_empty())
+		.map(|name| match qml {
+			true => ctype(&format!("QML:{name}"), typespec).name,
+			false => name.to_string(),
+		})
+		.collect()
+}
+
+/// The name `class` is registered with in `module`, if any.
+fn qml_name<'a>(module: &str, class: &Class, typespec: &'a TypeSpec) -> Option<&'a String> {
+	typespec
+		.typemap
+		.iter()
+		.find(|t| t.cname == class.name && t.module.as_ref().map(|v| v as &str) == Some(module))
+		// anonymous types are registered without exports
+		.filter(|t| !t.anonymous)
+		.map(|t| &t.name)
+}
+
+/// Writes the `exports` of a type registered as `qml_name`.
+fn write_exports(w: &mut Writer, module: &str, version: (u32, u32), qml_name: &str) {
+	let (major, minor) = version;
+
+	w.field(
+		"exports",
+		&format!(
+			"[{}]",
+			quote(&format!("{module}/{qml_name} {major}.{minor}"))
+		),
+	);
+	w.field(
+		"exportMetaObjectRevisions",
+		&format!("[{}]", major << 8 | minor),
+	);
+}
+
+fn write_class(
+	w: &mut Writer,
+	module: &str,
+	version: (u32, u32),
+	class: &Class,
+	typespec: &TypeSpec,
+) {
+	let qml_name = qml_name(module, class, typespec);
+
+	w.open("Component");
+	w.field("name", &quote(&class.name));
+	w.field("accessSemantics", &quote("reference"));
+
+	let prototype = ctype(&class.superclass, typespec).name;
+	if !prototype.is_empty() {
+		w.field("prototype", &quote(&prototype));
+		w.referenced.push(prototype);
+	}
+
+	if let Some(extension) = &class.extension {
+		w.field("extension", &quote(extension));
+		w.referenced.push(extension.clone());
+	}
+
+	if let Some(qml_name) = qml_name {
+		write_exports(w, module, version, qml_name);
+	}
+
+	if class.singleton {
+		w.field("isSingleton", "true");
+	}
+
+	if class.uncreatable || qml_name.is_none() {
+		w.field("isCreatable", "false");
+	}
+
+	if let Some(default) = class.properties.iter().find(|p| p.default) {
+		w.field("defaultProperty", &quote(&default.name));
+	}
+
+	for enum_ in &class.enums {
+		write_enum(w, enum_);
+	}
+
+	for prop in &class.properties {
+		write_property(w, prop, typespec);
+	}
+
+	for signal in &class.signals {
+		w.open("Signal");
+		w.field("name", &quote(&signal.name));
+		write_params(w, &signal.params, typespec);
+		w.close();
+	}
+
+	// notify signals are not documented, but tooling needs them for `on<Prop>Changed` handlers
+	for notify in class.properties.iter().filter_map(|p| p.notify.as_ref()) {
+		if !class.signals.iter().any(|s| &s.name == notify) {
+			w.open("Signal");
+			w.field("name", &quote(notify));
+			w.close();
+		}
+	}
+
+	for func in &class.functions {
+		w.open("Method");
+		w.field("name", &quote(&func.name));
+
+		let ret = ctype(&func.ret, typespec);
+		if ret.name != "void" {
+			w.field("type", &quote(&ret.name));
+
+			if ret.pointer {
+				w.field("isPointer", "true");
+			}
+		}
+
+		write_params(w, &func.params, typespec);
+		w.close();
+	}
+
+	w.close();
+}
+
+fn write_gadget(w: &mut Writer, gadget: &Gadget, typespec: &TypeSpec) {
+	w.open("Component");
+	w.field("name", &quote(&gadget.cname));
+	w.field("accessSemantics", &quote("value"));
+
+	for prop in &gadget.properties {
+		write_property(w, prop, typespec);
+	}
+
+	w.close();
+}
+
+fn write_namespace_enum(w: &mut Writer, module: &str, version: (u32, u32), enum_: &Enum) {
+	let cname = enum_.cname.as_ref().unwrap_or(&enum_.name);
+	let namespace = cname.split_once("::").map(|(ns, _)| ns).unwrap_or(cname);
+
+	w.open("Component");
+	w.field("name", &quote(namespace));
+	w.field("accessSemantics", &quote("none"));
+	write_exports(w, module, version, &enum_.name);
+	w.field("isCreatable", "false");
+	write_enum(w, enum_);
+	w.close();
+}
+
+fn write_enum(w: &mut Writer, enum_: &Enum) {
+	let name = enum_
+		.cname
+		.as_ref()
+		.and_then(|cname| cname.rsplit_once("::"))
+		.map(|(_, name)| name)
+		.unwrap_or(&enum_.name);
+
+	let values = enum_
+		.varaints
+		.iter()
+		.map(|v| quote(&v.name))
+		.collect::<Vec<_>>()
+		.join(", ");
+
+	w.open("Enum");
+	w.field("name", &quote(name));
+	w.field("values", &format!("[{values}]"));
+	w.close();
+}
+
+fn write_property(w: &mut Writer, prop: &Property, typespec: &TypeSpec) {
+	let type_ = ctype(&prop.type_, typespec);
+
+	w.open("Property");
+	w.field("name", &quote(&prop.name));
+	w.field("type", &quote(&type_.name));
+	w.referenced.push(type_.name.clone());
+
+	if type_.list {
+		w.field("isList", "true");
+	}
+
+	if type_.pointer {
+		w.field("isPointer", "true");
+	}
+
+	if !prop.writable {
+		w.field("isReadonly", "true");
+	}
+
+	if let Some(notify) = &prop.notify {
+		w.field("notify", &quote(notify));
+	}
+
+	w.close();
+}
+
+fn write_params(w: &mut Writer, params: &[FnParam], typespec: &TypeSpec) {
+	for param in params {
+		let type_ = ctype(&param.type_, typespec);
+
+		w.open("Parameter");
+		w.field("name", &quote(&param.name));
+		w.field("type", &quote(&type_.name));
+
+		if type_.pointer {
+			w.field("isPointer", "true");
+		}
+
+		w.close();
+	}
+}
+
+struct CType {
+	name: String,
+	list: bool,
+	pointer: bool,
+}
+
+/// c++ types qmltypes files use for QML value types, which are registered for several c++ types.
+const BUILTINS: &[(&str, &str)] = &[
+	("bool", "bool"),
+	("int", "int"),
+	("real", "double"),
+	("double", "double"),
+	("string", "QString"),
+	("url", "QUrl"),
+	("var", "QVariant"),
+	("variant", "QVariant"),
+	("date", "QDateTime"),
+	("color", "QColor"),
+	("point", "QPointF"),
+	("size", "QSizeF"),
+	("rect", "QRectF"),
+];
+
+/// Converts a typespec type into the c++ type name qmltypes expects.
+fn ctype(type_: &str, typespec: &TypeSpec) -> CType {
+	if let Some(qmlname) = type_.strip_prefix("QML:") {
+		if let Some(inner) = qmlname
+			.strip_prefix("list<")
+			.and_then(|t| t.strip_suffix('>'))
+		{
+			return CType {
+				list: true,
+				..ctype(&format!("QML:{}", inner.trim()), typespec)
+			}
+		}
+
+		if let Some((_, cname)) = BUILTINS.iter().find(|(name, _)| *name == qmlname) {
+			return CType {
+				name: cname.to_string(),
+				list: false,
+				pointer: false,
+			}
+		}
+
+		let cname = typespec
+			.typemap
+			.iter()
+			.find(|t| t.name == qmlname && !t.cname.is_empty() && !t.anonymous)
+			.map(|t| &t.cname[..])
+			.unwrap_or(qmlname);
+
+		let pointer = typespec.classes.iter().any(|c| c.name == cname)
+			|| typespec
+				.typemap
+				.iter()
+				.any(|t| t.name == qmlname && t.module.is_some());
+
+		return CType {
+			name: cname.to_string(),
+			list: false,
+			pointer,
+		}
+	}
+
+	let type_ = type_
+		.trim()
+		.trim_start_matches("const ")
+		.trim_end_matches('&');
+
+	if let Some(inner) = type_
+		.strip_prefix("QQmlListProperty<")
+		.and_then(|t| t.strip_suffix('>'))
+	{
+		let inner = inner.trim_end_matches('*');
+		return CType {
+			name: inner.to_string(),
+			list: true,
+			pointer: true,
+		}
+	}
+
+	match type_.strip_suffix('*') {
+		Some(type_) => CType {
+			name: type_.trim().to_string(),
+			list: false,
+			pointer: true,
+		},
+		None => CType {
+			name: type_.to_string(),
+			list: false,
+			pointer: false,
+		},
+	}
+}
+
+fn quote(s: &str) -> String {
+	// qml string literals accept json escapes
+	serde_json::to_string(s).unwrap()
+}
+
+struct Writer {
+	text: String,
+	depth: usize,
+	/// c++ names of types used by the written components
+	referenced: Vec<String>,
+}
+
+impl Writer {
+	fn new() -> Self {
+		Self {
+			text: String::new(),
+			depth: 0,
+			referenced: Vec::new(),
+		}
+	}
+
+	fn line(&mut self, line: &str) {
+		if !line.is_empty() {
+			for _ in 0..self.depth {
+				self.text += "    ";
+			}
+		}
+
+		self.text += line;
+		self.text += "\n";
+	}
+
+	fn open(&mut self, name: &str) {
+		self.line(&format!("{name} {{"));
+		self.depth += 1;
+	}
+
+	fn close(&mut self) {
+		self.depth -= 1;
+		self.line("}");
+	}
+
+	fn field(&mut self, name: &str, value: &str) {
+		self.line(&format!("{name}: {value}"));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{testutil, typespec::SourceLocation};
+
+	fn gadget(cname: &str, properties: &[(&str, &str)]) -> Gadget {
+		Gadget {
+			cname: cname.to_string(),
+			properties: properties
+				.iter()
+				.map(|(name, type_)| testutil::property(name, type_))
+				.collect(),
+		}
+	}
+
+	/// Names of the components in a generated qmltypes file.
+	fn components(qmltypes: &str) -> Vec<&str> {
+		qmltypes
+			.lines()
+			.zip(qmltypes.lines().skip(1))
+			.filter(|(line, _)| line.trim() == "Component {")
+			.filter_map(|(_, name)| name.trim().strip_prefix("name: "))
+			.collect()
+	}
+
+	#[test]
+	fn used_gadgets() {
+		let mut thing = testutil::class("Thing", "QObject");
+		let mut f = testutil::function("f", &["QList<ParamGadget>"]);
+		f.ret = "RetGadget".to_string();
+		thing.functions = vec![f];
+
+		let mut typespec = testutil::typespec(vec![thing]);
+		typespec.gadgets = vec![
+			gadget("ParamGadget", &[("inner", "NestedGadget")]),
+			gadget("RetGadget", &[]),
+			gadget("NestedGadget", &[]),
+			gadget("UnusedGadget", &[]),
+		];
+
+		let (qmltypes, _) = gen_qmltypes(testutil::MODULE, (1, 0), "test.qmltypes", &typespec);
+
+		assert_eq!(components(&qmltypes), [
+			r#""Thing""#,
+			r#""ParamGadget""#,
+			r#""RetGadget""#,
+			r#""NestedGadget""#,
+		]);
+	}
+
+	#[test]
+	fn qml_files() {
+		let mut widget = testutil::class("Widget", "QQuickItem");
+		widget.source = Some(SourceLocation {
+			file: "core/Widget.qml".to_string(),
+			line: 1,
+		});
+
+		let typespec = testutil::typespec(vec![testutil::class("Thing", "QObject"), widget]);
+		let (qmltypes, qmldir) = gen_qmltypes(testutil::MODULE, (1, 2), "test.qmltypes", &typespec);
+
+		// components defined by qml files are only listed in qmldir
+		assert_eq!(components(&qmltypes), [r#""Thing""#]);
+		assert_eq!(
+			qmldir,
+			"module Quickshell\ntypeinfo test.qmltypes\nWidget 1.2 Widget.qml\n"
+		);
+	}
+}
@@ -32,13 +32,17 @@ impl ReformatPass for GfmQuoteBlocks {
 	}
 }
 
+/// Rewrites `@@Module.Type.member` links into `TYPE99M..99TYPE` placeholders for the site.
+///
+/// Functions may select an overload by parameter count or types, e.g. `@@Type.foo(2)` or
+/// `@@Type.foo(int, string)`, which is kept as `99O2` or `99Oint,string` after the member type.
 pub struct TypeLinks;
 
 impl ReformatPass for TypeLinks {
 	fn reformat(&self, context: &Context, text: &mut String) {
 		let lines = text.lines().map(|line| {
 			if line.contains("@@") {
-				let mut src: &str = &*line;
+				let mut src: &str = line;
 				let mut accum = String::new();
 
 				while let Some(i) = src.find("@@") {
@@ -53,16 +57,25 @@ impl ReformatPass for TypeLinks {
 						(':', false),
 					];
 
+					// overload selectors may contain separators, e.g. `foo(int, string)`
+					let skip = src
+						.find('(')
+						.filter(|open| {
+							!src[..*open].contains(|c| separators.iter().any(|(sc, _)| c == *sc))
+						})
+						.and_then(|open| src[open..].find(')').map(|close| open + close))
+						.unwrap_or(0);
+
 					let (mut end, mut ty) = src
-						.chars()
-						.enumerate()
+						.char_indices()
+						.skip_while(|(i, _)| *i < skip)
 						.find_map(|(i, char)| {
 							separators
 								.iter()
 								.find(|(sc, _)| char == *sc)
 								.map(|(_, strip)| (i + if *strip { 1 } else { 0 }, &src[..i]))
 						})
-						.unwrap_or_else(|| (src.len(), src));
+						.unwrap_or((src.len(), src));
 
 					// special case for . as it is contained in valid types as well
 					if ty.ends_with('.') {
@@ -76,18 +89,13 @@ impl ReformatPass for TypeLinks {
 						Some(_) => {
 							let mut split = ty.rsplit_once('.').unwrap_or(("", ty));
 
-							let member = split
-								.1
-								.chars()
-								.next()
-								.unwrap()
-								.is_lowercase()
-								.then(|| {
-									let prop = split.1;
-									split = split.0.rsplit_once('.').unwrap_or(("", split.0));
-									prop
-								})
-								.unwrap_or("");
+							let member = if split.1.chars().next().unwrap().is_lowercase() {
+								let prop = split.1;
+								split = split.0.rsplit_once('.').unwrap_or(("", split.0));
+								prop
+							} else {
+								""
+							};
 
 							let (mut module, name) = split;
 
@@ -99,11 +107,18 @@ impl ReformatPass for TypeLinks {
 						},
 					};
 
+					let mut overload = None;
+
 					let (membertype, membername) = match member {
 						None => ("", ""),
 						Some(name) if name.ends_with("()") => ("func", &name[..name.len() - 2]),
 						Some(name) if name.ends_with("(s)") => ("signal", &name[..name.len() - 3]),
-						Some(name) if name.is_empty() => ("", ""),
+						Some(name) if name.ends_with(')') && name.contains('(') => {
+							let (name, selector) = name[..name.len() - 1].split_once('(').unwrap();
+							overload = Some(selector.replace(' ', ""));
+							("func", name)
+						},
+						Some("") => ("", ""),
 						Some(name) => ("prop", name),
 					};
 
@@ -126,18 +141,76 @@ impl ReformatPass for TypeLinks {
 						accum += &format!("99V{membername}99T{membertype}");
 					}
 
+					if let Some(overload) = overload {
+						accum += &format!("99O{overload}");
+					}
+
 					accum += "99TYPE";
 					src = &src[end..];
 				}
 
 				accum += src;
 
-				return Cow::Owned(accum);
+				Cow::Owned(accum)
 			} else {
-				return Cow::Borrowed(line);
+				Cow::Borrowed(line)
 			}
 		});
 
 		*text = lines.fold(String::new(), |accum, line| accum + line.as_ref() + "\n");
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn links(text: &str) -> String {
+		let mut text = text.to_string();
+		TypeLinks.reformat(
+			&Context {
+				module: "Quickshell",
+			},
+			&mut text,
+		);
+		text.trim_end().to_string()
+	}
+
+	#[test]
+	fn members() {
+		assert_eq!(
+			links("see @@Quickshell.Foo.bar, @@Foo.baz() and @@Foo.changed(s)."),
+			"see TYPE99MQS_Quickshell99NFoo99Vbar99Tprop99TYPE, \
+			 TYPE99MQS_Quickshell99NFoo99Vbaz99Tfunc99TYPE and \
+			 TYPE99MQS_Quickshell99NFoo99Vchanged99Tsignal99TYPE."
+		);
+		assert_eq!(
+			links("@@QtQuick.Item"),
+			"TYPE99MQT_qml_QtQuick99NItem99TYPE"
+		);
+	}
+
+	#[test]
+	fn overload_selectors() {
+		assert_eq!(
+			links("@@Foo.bar(2), @@bar(int, QList<QString>) or @@Foo.bar(string)$s"),
+			"TYPE99MQS_Quickshell99NFoo99Vbar99Tfunc99O299TYPE, \
+			 TYPE99Vbar99Tfunc99Oint,QList<QString>99TYPE or \
+			 TYPE99MQS_Quickshell99NFoo99Vbar99Tfunc99Ostring99TYPEs"
+		);
+	}
+
+	#[test]
+	fn non_ascii_text() {
+		// selectors are found by byte offset, which differs from the char count here
+		assert_eq!(
+			links("é — @@Foo.bar(int, string) — ü @@Foo.baz, ok"),
+			"é — TYPE99MQS_Quickshell99NFoo99Vbar99Tfunc99Oint,string99TYPE — ü \
+			 TYPE99MQS_Quickshell99NFoo99Vbaz99Tprop99TYPE, ok"
+		);
+		assert_eq!(
+			links("@@Foo.bär(int, string) x"),
+			"TYPE99MQS_Quickshell99NFoo99Vbär99Tfunc99Oint,string99TYPE x"
+		);
+	}
+}
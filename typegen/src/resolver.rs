@@ -1,140 +1,432 @@
-use std::collections::HashMap;
+use std::{
+	cell::RefCell,
+	collections::{BTreeMap, HashMap, HashSet},
+};
+
+use anyhow::bail;
 
 use crate::{
 	outform::{self, Flag, Parameter, PropertyType},
-	typespec::{FnParam, Function, Property, Signal, TypeSpec},
+	parse::{split_top_level, MemberOrder},
+	typespec::{
+		Class,
+		Deprecation,
+		Enum,
+		FnParam,
+		Function,
+		Gadget,
+		Property,
+		QmlTypeMapping,
+		Signal,
+		SourceLocation,
+		TypeSpec,
+	},
 };
 
-pub fn resolve_types(
-	module: &str,
-	typespec: TypeSpec,
-) -> anyhow::Result<HashMap<String, outform::TypeInfo>> {
-	let mut outtypes = HashMap::new();
+/// A type referenced by a documented member that could not be found in any typespec.
+#[derive(Debug)]
+pub struct UnresolvedType {
+	/// the c++ or `QML:` type name as written in the typespec
+	pub name: String,
+	pub module: String,
+	/// qml name of the type the member is documented on
+	pub class: String,
+	/// description of the member, e.g. "property `foo`"
+	pub member: String,
+	pub source: Option<SourceLocation>,
+}
 
-	let types = typespec
-		.typemap
-		.iter()
-		.filter(|type_| type_.module.as_ref().map(|v| v as &str) == Some(module));
+/// A member overriding an inherited one of the same name in an incompatible way.
+#[derive(Debug)]
+pub struct MismatchedOverride {
+	pub module: String,
+	/// qml name of the type the member is documented on
+	pub class: String,
+	/// description of the member, e.g. "property `foo`"
+	pub member: String,
+	/// c++ class declaring the overriding member
+	pub class_declaring: String,
+	/// c++ class declaring the overridden member
+	pub base: String,
+	/// what the override changes, e.g. "makes a writable property readonly"
+	pub reason: String,
+	pub source: Option<SourceLocation>,
+}
 
-	let findqmltype = |name: &str| {
-		if name.starts_with("QML:") {
-			println!("QML? {name}");
-			typespec
-				.typemap
-				.iter()
-				.find(|type_| type_.name == name[4..])
-		} else {
-			typespec.typemap.iter().find(|type_| type_.cname == name)
+pub struct Resolved {
+	pub types: BTreeMap<String, outform::TypeInfo>,
+	pub unresolved: Vec<UnresolvedType>,
+	pub overrides: Vec<MismatchedOverride>,
+}
+
+struct Report<'a> {
+	module: &'a str,
+	class: &'a str,
+	unresolved: &'a mut Vec<UnresolvedType>,
+	overrides: &'a mut Vec<MismatchedOverride>,
+}
+
+impl Report<'_> {
+	/// Records `ctype` as unresolved if any part of `type_` could not be resolved.
+	fn check(
+		&mut self,
+		type_: &outform::Type,
+		ctype: &str,
+		member: impl FnOnce() -> String,
+		source: &Option<SourceLocation>,
+	) {
+		if !is_resolved(type_) {
+			self.unresolved.push(UnresolvedType {
+				name: ctype.to_string(),
+				module: self.module.to_string(),
+				class: self.class.to_string(),
+				member: member(),
+				source: source.clone(),
+			});
 		}
-	};
+	}
 
-	for mapping in types {
-		let Some(class) = typespec
-			.classes
-			.iter()
-			.find(|class| class.name == mapping.cname)
-		else {
-			continue
-		};
+	/// Records every way `member`, declared by `class`, changes the inherited `base` it overrides.
+	fn check_override<T: Member>(
+		&mut self,
+		member: &T,
+		class: &Class,
+		base: &T,
+		baseclass: &Class,
+	) {
+		for reason in member.mismatches(base) {
+			self.overrides.push(MismatchedOverride {
+				module: self.module.to_string(),
+				class: self.class.to_string(),
+				member: member.describe(),
+				class_declaring: class.name.clone(),
+				base: baseclass.name.clone(),
+				reason,
+				source: member.source().clone(),
+			});
+		}
+	}
+}
 
-		let mut properties = Vec::<&Property>::new();
-		let mut functions = Vec::<&Function>::new();
-		let mut signals = Vec::<&Signal>::new();
+/// Generic c++ types converted to js objects in QML.
+const MAP_TYPES: &[&str] = &[
+	"QMap",
+	"QHash",
+	"QMultiMap",
+	"QMultiHash",
+	"QPair",
+	"std::pair",
+	"std::map",
+	"std::unordered_map",
+];
 
-		// the first superclass availible from QML
-		let mut superclass = &class.superclass;
-		let superclass = loop {
-			let type_ = findqmltype(superclass);
+/// Lookup tables over a typespec, built once instead of scanning it for every member.
+///
+/// Where several entries share a key the first one wins, as with a linear search.
+struct Index<'a> {
+	/// qml types by module
+	modules: HashMap<&'a str, Vec<&'a QmlTypeMapping>>,
+	/// module being documented, whose types shadow imported ones
+	module: &'a str,
+	/// named qml types visible from the module by qml name, at most one per module
+	qml_names: HashMap<&'a str, Vec<&'a QmlTypeMapping>>,
+	/// qml names referenced while resolving that are provided by multiple modules
+	ambiguous: RefCell<BTreeMap<&'a str, Vec<&'a str>>>,
+	/// modules with classes or enums in the typespec, which are documented alongside this one
+	local_modules: HashSet<&'a str>,
+	/// qml types by c++ name, including anonymous ones
+	cnames: HashMap<&'a str, &'a QmlTypeMapping>,
+	classes: HashMap<&'a str, &'a Class>,
+	gadgets: HashMap<&'a str, &'a Gadget>,
+	/// enums by c++ name, e.g. `Foo::Enum`
+	enums: HashMap<&'a str, &'a Enum>,
+}
 
-			if let Some(type_) = type_ {
-				break outform::Type::resolve(type_.module.as_ref().map(|v| v as &str), &type_.name)
+impl<'a> Index<'a> {
+	fn new(typespec: &'a TypeSpec, module: &'a str, imports: &[String]) -> Self {
+		let mut index = Self {
+			modules: HashMap::new(),
+			module,
+			qml_names: HashMap::new(),
+			ambiguous: RefCell::new(BTreeMap::new()),
+			local_modules: HashSet::new(),
+			cnames: HashMap::new(),
+			classes: HashMap::new(),
+			gadgets: HashMap::new(),
+			enums: HashMap::new(),
+		};
+
+		for type_ in &typespec.typemap {
+			if let Some(module) = &type_.module {
+				index.modules.entry(module).or_default().push(type_);
 			}
 
-			let superctype = typespec
-				.classes
-				.iter()
-				.find(|class| &class.name == superclass);
-
-			match superctype {
-				Some(superctype) => {
-					properties.extend(superctype.properties.iter());
-					functions.extend(superctype.functions.iter());
-					signals.extend(superctype.signals.iter());
-					superclass = &superctype.superclass;
+			// builtins without a module are always visible
+			let visible = match type_.module.as_deref() {
+				None => true,
+				Some(m) => {
+					m == module
+						|| imports.is_empty()
+						|| imports
+							.iter()
+							.any(|i| m == i || m.strip_prefix("qml.") == Some(i))
 				},
-				None => break outform::Type::unknown(),
+			};
+
+			if !type_.anonymous && visible {
+				let types = index.qml_names.entry(&type_.name).or_default();
+
+				// the same qml type may be registered for several c++ types, e.g. `int`
+				if !types.iter().any(|t| t.module == type_.module) {
+					types.push(type_);
+				}
 			}
+
+			if !type_.cname.is_empty() {
+				index.cnames.entry(&type_.cname).or_insert(type_);
+			}
+		}
+
+		for class in &typespec.classes {
+			index.classes.entry(&class.name).or_insert(class);
+			index.local_modules.insert(&class.module);
+		}
+
+		for gadget in &typespec.gadgets {
+			index.gadgets.entry(&gadget.cname).or_insert(gadget);
+		}
+
+		for enum_ in &typespec.enums {
+			if let Some(cname) = enum_.cname.as_deref().filter(|c| !c.is_empty()) {
+				index.enums.entry(cname).or_insert(enum_);
+			}
+
+			if let Some(module) = &enum_.module {
+				index.local_modules.insert(module);
+			}
+		}
+
+		index
+	}
+
+	/// Finds a type visible from QML by its `QML:` name or c++ name.
+	///
+	/// Anonymous types are not visible by name, so their members are inherited instead.
+	fn qml_type(&self, name: &str) -> Option<&'a QmlTypeMapping> {
+		match name.strip_prefix("QML:") {
+			Some(name) => self.qml_name(name),
+			None => self.cnames.get(name).copied().filter(|t| !t.anonymous),
+		}
+	}
+
+	/// Finds a type by its qml name among the types visible from the documented module.
+	///
+	/// Qualified names are looked up by their longest known suffix, as inline components are
+	/// named after their file, e.g. `Foo.Bar`, but import namespaces such as `QtQuick.Item` are
+	/// not tracked. Names provided by several imported modules are recorded as ambiguous and
+	/// not resolved.
+	fn qml_name(&self, name: &str) -> Option<&'a QmlTypeMapping> {
+		let (name, types) = std::iter::successors(Some(name), |name| {
+			name.split_once('.').map(|(_, rest)| rest)
+		})
+		.find_map(|name| self.qml_names.get_key_value(name))?;
+
+		if let Some(own) = types
+			.iter()
+			.find(|t| t.module.as_deref() == Some(self.module))
+		{
+			return Some(own)
+		}
+
+		match types[..] {
+			[type_] => Some(type_),
+			_ => {
+				// named as in `imports`
+				let modules = types.iter().map(|t| match t.module.as_deref() {
+					Some(m) => m.strip_prefix("qml.").unwrap_or(m),
+					None => "qml",
+				});
+				self.ambiguous.borrow_mut().insert(name, modules.collect());
+				None
+			},
+		}
+	}
+
+	/// Resolves a type registered in `module`, which is local if documented by the typespec
+	/// and from Qt otherwise.
+	fn resolve(&self, module: &Option<String>, name: &str) -> outform::Type {
+		let src = match module {
+			Some(module) if self.local_modules.contains(&module[..]) => outform::TypeSource::Local,
+			_ => outform::TypeSource::Qt,
 		};
 
-		fn qmlparamtype(ctype: &str, typespec: &TypeSpec) -> outform::Type {
-			if ctype.starts_with("QML:") {
-				return match typespec
-					.typemap
-					.iter()
-					.find(|type_| type_.name == ctype[4..])
-				{
-					Some(t) => {
-						outform::Type::resolve(t.module.as_ref().map(|v| v as &str), &t.name)
-					},
-					None => outform::Type::unknown(),
+		outform::Type::resolve(src, module.as_deref(), name)
+	}
+
+	fn class(&self, cname: &str) -> Option<&'a Class> {
+		self.classes.get(cname).copied()
+	}
+
+	/// Finds the type or enum registered for a c++ type that may be qualified with more
+	/// namespaces than it was registered with, e.g. `qs::Foo::Bar` for `Foo::Bar`.
+	fn ctype(&self, ctype: &str) -> Option<outform::Type> {
+		let type_ = scopes(ctype)
+			.find_map(|name| self.cnames.get(name))
+			.map(|type_| {
+				let mut t = self.resolve(&type_.module, &type_.name);
+
+				if type_.anonymous {
+					t.type_ = outform::TypeSource::Anonymous;
 				}
-			}
 
-			let (mut ctype, of) = match ctype.split_once('<') {
-				None => (ctype, None),
-				Some((ctype, mut remaining)) => {
-					if remaining.ends_with('*') {
-						remaining = &remaining[0..remaining.len() - 1];
-					}
+				t
+			});
+
+		type_.or_else(|| {
+			scopes(ctype)
+				.find_map(|name| self.enums.get(name))
+				.map(|enum_| self.resolve(&enum_.module, &enum_.name))
+		})
+	}
+}
+
+/// A c++ name followed by each less qualified name, e.g. `a::B::C`, `B::C` and `C`.
+fn scopes(name: &str) -> impl Iterator<Item = &str> {
+	std::iter::successors(Some(name), |name| {
+		name.split_once("::").map(|(_, rest)| rest)
+	})
+}
+
+fn is_resolved(type_: &outform::Type) -> bool {
+	!matches!(type_.type_, outform::TypeSource::Unknown) && type_.args.iter().all(is_resolved)
+}
+
+pub fn resolve_types(
+	module: &str,
+	imports: &[String],
+	typespec: TypeSpec,
+	order: MemberOrder,
+) -> anyhow::Result<Resolved> {
+	let mut outtypes = BTreeMap::new();
+	let mut unresolved = Vec::new();
+	let mut overrides = Vec::new();
+
+	let index = Index::new(&typespec, module, imports);
+
+	let types = index
+		.modules
+		.get(module)
+		.into_iter()
+		.flatten()
+		// anonymous types have no page of their own
+		.filter(|type_| !type_.anonymous);
+
+	// sections are only shown when grouping
+	let group = |group: &Option<String>| group.clone().filter(|_| order == MemberOrder::Grouped);
+
+	for mapping in types {
+		let Some(class) = index.class(&mapping.cname) else { continue };
+
+		let mut report = Report {
+			module,
+			class: &mapping.name,
+			unresolved: &mut unresolved,
+			overrides: &mut overrides,
+		};
+
+		let (declaring, qmlsuper) = declaring_classes(&index, class);
+
+		// the first superclass availible from QML
+		let superclass = match index.qml_type(qmlsuper) {
+			Some(type_) => index.resolve(&type_.module, &type_.name),
+			None => {
+				let type_ = outform::Type::unknown();
+				report.check(&type_, qmlsuper, || "superclass".to_string(), &class.source);
+				type_
+			},
+		};
+
+		if let Some(cname) = class
+			.extension
+			.as_ref()
+			.filter(|e| index.class(e).is_none())
+		{
+			let type_ = outform::Type::unknown();
+			report.check(&type_, cname, || "extension".to_string(), &class.source);
+		}
+
+		fn qmlparamtype(ctype: &str, index: &Index) -> outform::Type {
+			let ctype = match ctype.trim_end_matches('*') {
+				"QVariantMap" => "QMap<QString, QVariant>",
+				"QVariantHash" => "QHash<QString, QVariant>",
+				"QVariantList" => "QList<QVariant>",
+				"QStringList" => "QList<QString>",
+				ctype => ctype,
+			};
+
+			let (qml, ctype) = match ctype.strip_prefix("QML:") {
+				Some(ctype) => (true, ctype),
+				None => (false, ctype),
+			};
 
-					// closing `>`
-					remaining = &remaining[0..remaining.len() - 1];
+			// type arguments, which may themselves be generic, e.g. `QList<QPair<int, int>>`
+			let (ctype, args) = match ctype.split_once('<') {
+				None => (ctype, Vec::new()),
+				Some((ctype, remaining)) => {
+					let remaining = remaining.trim_end_matches('*');
+					let remaining = remaining.strip_suffix('>').unwrap_or(remaining);
 
-					let of = Box::new(qmlparamtype(remaining, typespec));
+					let args = split_top_level(remaining, ',')
+						.into_iter()
+						.map(|arg| match qml {
+							true => qmlparamtype(&format!("QML:{}", arg.trim()), index),
+							false => qmlparamtype(arg.trim(), index),
+						})
+						.collect::<Vec<_>>();
 
-					(ctype, Some(of))
+					(ctype.trim(), args)
 				},
 			};
 
-			if ctype.ends_with('*') {
-				ctype = &ctype[0..ctype.len() - 1];
+			if qml {
+				return match index.qml_name(ctype) {
+					Some(t) => {
+						let mut t = index.resolve(&t.module, &t.name);
+						t.args = args;
+						t
+					},
+					None => outform::Type::unknown(),
+				}
 			}
 
-			// note: suffix is checked instead of == due to Q_PROPERTY using fully qualified names
-			let qtype = typespec
-				.typemap
-				.iter()
-				.find(|type_| !type_.cname.is_empty() && ctype.ends_with(&type_.cname))
-				.map(|type_| (&type_.module, &type_.name))
-				.or_else(|| {
-					typespec
-						.enums
-						.iter()
-						.find(|type_| {
-							type_
-								.cname
-								.as_ref()
-								.map(|v| v as &str)
-								.map(|v| !v.is_empty() && ctype.ends_with(v))
-								.unwrap_or(false)
-						})
-						.map(|type_| (&type_.module, &type_.name))
-				});
+			let ctype = ctype.trim_end_matches('*');
+
+			// maps and pairs are converted to js objects, documented as `var<key, value>`
+			if MAP_TYPES.iter().any(|map| ctype.ends_with(map)) {
+				let mut t = outform::Type::resolve(outform::TypeSource::Qt, None, "var");
+				t.args = args;
+				return t
+			}
 
-			match qtype {
-				Some((module, name)) => {
-					let mut t = outform::Type::resolve(module.as_ref().map(|v| v as &str), &name);
-					t.of = of;
+			// note: Q_PROPERTY may use more qualified names than the type was registered with
+			match index.ctype(ctype) {
+				Some(mut t) => {
+					t.args = args;
 					t
 				},
 				None => outform::Type::unknown(),
 			}
 		}
 
-		fn solveprop(prop: &Property, typespec: &TypeSpec) -> outform::Property {
+		fn solveprop(
+			name: &str,
+			prop: &Property,
+			index: &Index,
+			report: &mut Report,
+		) -> outform::Property {
 			let ctype = &prop.type_[..];
 
-			let flags = {
+			let mut flags = {
 				let mut flags = Vec::new();
 
 				if prop.default {
@@ -150,83 +442,337 @@ pub fn resolve_types(
 				flags
 			};
 
-			let gadget = typespec.gadgets.iter().find(|gadget| gadget.cname == ctype);
+			let replacement = solvedeprecation(&prop.deprecated, &mut flags);
+
+			let gadget = index.gadgets.get(ctype);
 
 			match gadget {
 				Some(gadget) => outform::Property {
+					name: prop.name.clone(),
+					group: None,
+					inherited_from: None,
 					type_: PropertyType::Gadget(
 						gadget
 							.properties
 							.iter()
-							.map(|prop| (prop.name.clone(), solveprop(prop, typespec).type_))
+							.map(|gprop| {
+								let name = format!("{name}.{}", gprop.name);
+								let type_ = solveprop(&name, gprop, index, report).type_;
+								(gprop.name.clone(), type_)
+							})
 							.collect(),
 					),
 					details: prop.details.clone(),
 					flags,
+					replacement,
+					source: prop.source.as_ref().map(solvesource),
+					since: None,
 				},
-				None => outform::Property {
-					type_: PropertyType::Type(qmlparamtype(ctype, typespec)),
-					details: prop.details.clone(),
-					flags,
+				None => {
+					let mut type_ = qmlparamtype(ctype, index);
+					type_.nullable = prop.nullable;
+					report.check(&type_, ctype, || format!("property `{name}`"), &prop.source);
+
+					outform::Property {
+						name: prop.name.clone(),
+						group: None,
+						inherited_from: None,
+						type_: PropertyType::Type(type_),
+						details: prop.details.clone(),
+						flags,
+						replacement,
+						source: prop.source.as_ref().map(solvesource),
+						since: None,
+					}
 				},
 			}
 		}
 
-		fn solvefunc(func: &Function, typespec: &TypeSpec) -> outform::Function {
+		fn solvefunc(func: &Function, index: &Index, report: &mut Report) -> outform::Function {
+			let mut ret = qmlparamtype(&func.ret, index);
+			ret.nullable = func.nullable;
+			report.check(
+				&ret,
+				&func.ret,
+				|| format!("return type of function `{}`", func.name),
+				&func.source,
+			);
+
+			let mut flags = Vec::new();
+			let replacement = solvedeprecation(&func.deprecated, &mut flags);
+
+			let params = func
+				.params
+				.iter()
+				.map(
+					|FnParam {
+					     type_: ctype,
+					     name,
+					     default,
+					     nullable,
+					 }| {
+						let mut type_ = qmlparamtype(ctype, index);
+						type_.nullable = *nullable;
+						report.check(
+							&type_,
+							ctype,
+							|| format!("parameter `{name}` of function `{}`", func.name),
+							&func.source,
+						);
+
+						Parameter {
+							name: name.clone(),
+							type_,
+							default: default.clone(),
+						}
+					},
+				)
+				.collect::<Vec<_>>();
+
 			outform::Function {
-				ret: qmlparamtype(&func.ret, typespec),
+				ret,
 				name: func.name.clone(),
-				id: func.name.clone(),
+				// see solveoverloads
+				id: format!("{}({})", func.name, signature(&func.params, &params)),
+				signature: declared_signature(&func.params),
+				arity_id: None,
+				name_id: None,
+				overloads: Vec::new(),
+				group: None,
+				inherited_from: None,
 				details: func.details.clone(),
-				params: func
-					.params
-					.iter()
-					.map(|FnParam { type_, name }| Parameter {
-						name: name.clone(),
-						type_: qmlparamtype(type_, typespec),
-					})
-					.collect(),
+				params,
+				flags,
+				replacement,
+				source: func.source.as_ref().map(solvesource),
+				since: None,
 			}
 		}
 
-		fn solvesignal(func: &Signal, typespec: &TypeSpec) -> outform::Signal {
+		fn solvesignal(func: &Signal, index: &Index, report: &mut Report) -> outform::Signal {
+			let mut flags = Vec::new();
+			let replacement = solvedeprecation(&func.deprecated, &mut flags);
+
 			outform::Signal {
 				name: func.name.clone(),
+				group: None,
+				inherited_from: None,
 				details: func.details.clone(),
 				params: func
 					.params
 					.iter()
-					.map(|FnParam { type_, name }| Parameter {
-						name: name.clone(),
-						type_: qmlparamtype(type_, typespec),
-					})
+					.map(
+						|FnParam {
+						     type_: ctype,
+						     name,
+						     default,
+						     nullable,
+						 }| {
+							let mut type_ = qmlparamtype(ctype, index);
+							type_.nullable = *nullable;
+							report.check(
+								&type_,
+								ctype,
+								|| format!("parameter `{name}` of signal `{}`", func.name),
+								&func.source,
+							);
+
+							Parameter {
+								name: name.clone(),
+								type_,
+								default: default.clone(),
+							}
+						},
+					)
 					.collect(),
+				flags,
+				replacement,
+				source: func.source.as_ref().map(solvesource),
+				since: None,
 			}
 		}
 
-		properties.extend(class.properties.iter());
-		properties.sort_by(|a, b| Ord::cmp(&a.name, &b.name));
-
-		functions.extend(class.functions.iter());
-		functions.sort_by(|a, b| Ord::cmp(&a.name, &b.name));
+		let properties = collect_members(&declaring, |c| &c.properties, order, &mut report)
+			.into_iter()
+			.map(|(from, prop, details)| outform::Property {
+				group: group(&prop.group),
+				inherited_from: inherited_from(&declaring, from),
+				details: details.clone(),
+				..solveprop(&prop.name, prop, &index, &mut report)
+			})
+			.collect::<Vec<_>>();
 
-		signals.extend(class.signals.iter());
-		signals.sort_by(|a, b| Ord::cmp(&a.name, &b.name));
+		let mut functions = collect_members(&declaring, |c| &c.functions, order, &mut report)
+			.into_iter()
+			.map(|(from, func, details)| outform::Function {
+				group: group(&func.group),
+				inherited_from: inherited_from(&declaring, from),
+				details: details.clone(),
+				..solvefunc(func, &index, &mut report)
+			})
+			.collect::<Vec<_>>();
 
-		let properties = properties
-			.iter()
-			.map(|prop| (prop.name.clone(), solveprop(prop, &typespec)))
-			.collect::<HashMap<_, _>>();
+		mergeoverloads(&mut functions, |f| f);
+		solveoverloads(&mut functions, order);
 
-		let functions = functions
-			.iter()
-			.map(|func| solvefunc(func, &typespec))
+		let signals = collect_members(&declaring, |c| &c.signals, order, &mut report)
+			.into_iter()
+			.map(|(from, signal, details)| outform::Signal {
+				group: group(&signal.group),
+				inherited_from: inherited_from(&declaring, from),
+				details: details.clone(),
+				..solvesignal(signal, &index, &mut report)
+			})
 			.collect::<Vec<_>>();
 
-		let signals = signals
+		// ancestors visible from QML are documented on their own pages, but their members are
+		// listed here so the whole api of the type can be seen at once
+		let mut inherited = Vec::new();
+		let mut seen = HashSet::from([&class.name]);
+		let mut ancestor = index.qml_type(qmlsuper);
+
+		// members overridden by the type or listed under a nearer ancestor are not repeated
+		let mut listed_properties = declaring
+			.iter()
+			.flat_map(|c| c.properties.iter().map(Member::key))
+			.collect::<HashSet<_>>();
+		let mut listed_functions = declaring
 			.iter()
-			.map(|signal| (signal.name.clone(), solvesignal(signal, &typespec)))
-			.collect::<HashMap<_, _>>();
+			.flat_map(|c| c.functions.iter().map(Member::key))
+			.collect::<HashSet<_>>();
+		let mut listed_signals = declaring
+			.iter()
+			.flat_map(|c| c.signals.iter().map(Member::key))
+			.collect::<HashSet<_>>();
+
+		while let Some(ancestor_mapping) = ancestor {
+			let type_ = index.resolve(&ancestor_mapping.module, &ancestor_mapping.name);
+
+			// Qt types and types from outside the typespec have no members to list
+			let Some(ancestor_class) = index
+				.class(&ancestor_mapping.cname)
+				.filter(|_| matches!(type_.type_, outform::TypeSource::Local))
+				.filter(|c| seen.insert(&c.name))
+			else {
+				break
+			};
+
+			let (declaring, qmlsuper) = declaring_classes(&index, ancestor_class);
+
+			// unresolved types and overrides are reported on the ancestor's own page
+			let mut ignored = Report {
+				module,
+				class: &ancestor_mapping.name,
+				unresolved: &mut Vec::new(),
+				overrides: &mut Vec::new(),
+			};
+
+			let mut members = collect_members(&declaring, |c| &c.functions, order, &mut ignored)
+				.into_iter()
+				.map(|(_, func, _)| (func.key(), solvefunc(func, &index, &mut ignored)))
+				.collect::<Vec<_>>();
+			mergeoverloads(&mut members, |(_, func)| func);
+
+			// ids are given to every overload, as on the ancestor's page, but sorting is left
+			// until after filtering so they still line up with their keys
+			let (keys, mut functions) = members.into_iter().unzip::<_, _, Vec<_>, Vec<_>>();
+			solveoverloads(&mut functions, MemberOrder::Source);
+
+			let mut functions = keys
+				.into_iter()
+				.zip(functions)
+				.filter(|(key, _)| listed_functions.insert(key.clone()))
+				.map(|(_, func)| func.id)
+				.collect::<Vec<_>>();
+
+			if order == MemberOrder::Alpha {
+				functions.sort();
+			}
+
+			inherited.push(outform::InheritedInfo {
+				type_,
+				properties: collect_members(&declaring, |c| &c.properties, order, &mut ignored)
+					.into_iter()
+					.filter(|(_, prop, _)| listed_properties.insert(prop.key()))
+					.map(|(_, prop, _)| prop.name.clone())
+					.collect(),
+				functions,
+				signals: collect_members(&declaring, |c| &c.signals, order, &mut ignored)
+					.into_iter()
+					.filter(|(_, signal, _)| listed_signals.insert(signal.key()))
+					.map(|(_, signal, _)| signal.name.clone())
+					.collect(),
+			});
+
+			ancestor = index.qml_type(qmlsuper);
+		}
+
+		let attached = match &class.attached {
+			None => None,
+			Some(cname) => match index.class(cname) {
+				None => {
+					let type_ = outform::Type::unknown();
+					report.check(
+						&type_,
+						cname,
+						|| "attached object".to_string(),
+						&class.source,
+					);
+					None
+				},
+				Some(attached) => {
+					let mut declaring = vec![attached];
+					declaring.extend(hidden_superclasses(&index, attached).0);
+
+					Some(outform::AttachedInfo {
+						properties: collect_members(
+							&declaring,
+							|c| &c.properties,
+							order,
+							&mut report,
+						)
+						.into_iter()
+						.map(|(from, prop, details)| {
+							// reported as used from qml, e.g. `Foo.bar`
+							let name = format!("{}.{}", mapping.name, prop.name);
+
+							outform::Property {
+								group: group(&prop.group),
+								inherited_from: inherited_from(&declaring, from),
+								details: details.clone(),
+								..solveprop(&name, prop, &index, &mut report)
+							}
+						})
+						.collect(),
+						functions: {
+							let mut functions =
+								collect_members(&declaring, |c| &c.functions, order, &mut report)
+									.into_iter()
+									.map(|(from, func, details)| outform::Function {
+										group: group(&func.group),
+										inherited_from: inherited_from(&declaring, from),
+										details: details.clone(),
+										..solvefunc(func, &index, &mut report)
+									})
+									.collect::<Vec<_>>();
+
+							mergeoverloads(&mut functions, |f| f);
+							solveoverloads(&mut functions, order);
+							functions
+						},
+						signals: collect_members(&declaring, |c| &c.signals, order, &mut report)
+							.into_iter()
+							.map(|(from, signal, details)| outform::Signal {
+								group: group(&signal.group),
+								inherited_from: inherited_from(&declaring, from),
+								details: details.clone(),
+								..solvesignal(signal, &index, &mut report)
+							})
+							.collect(),
+					})
+				},
+			},
+		};
 
 		let coreenum = class.enums.iter().find(|e| e.name == "Enum");
 		let variants = match coreenum {
@@ -234,65 +780,757 @@ pub fn resolve_types(
 				.varaints
 				.iter()
 				.map(|variant| {
+					let mut flags = Vec::new();
+					let replacement = solvedeprecation(&variant.deprecated, &mut flags);
+
 					(variant.name.clone(), outform::Variant {
 						details: variant.details.clone(),
+						flags,
+						replacement,
+						since: None,
 					})
 				})
 				.collect(),
-			None => HashMap::new(),
+			None => BTreeMap::new(),
 		};
 
+		let mut flags = Vec::new();
+
+		if coreenum.is_some() {
+			flags.push(Flag::Enum);
+		} else if class.singleton {
+			flags.push(Flag::Singleton);
+		} else if class.uncreatable {
+			flags.push(Flag::Uncreatable);
+		}
+
+		// enums exposed as classes are deprecated through their enum
+		let deprecated = class
+			.deprecated
+			.as_ref()
+			.or(coreenum.and_then(|e| e.deprecated.as_ref()))
+			.cloned();
+		let replacement = solvedeprecation(&deprecated, &mut flags);
+
 		let type_ = outform::TypeInfo {
 			name: mapping.name.clone(),
 			module: mapping.module.clone().unwrap(),
-			details: outform::TypeDetails::Class(outform::ClassInfo {
+			details: outform::TypeDetails::Class(Box::new(outform::ClassInfo {
 				superclass,
 				description: class.description.clone(),
 				details: class.details.clone(),
-				flags: {
-					let mut flags = Vec::new();
-
-					if coreenum.is_some() {
-						flags.push(Flag::Enum);
-					} else if class.singleton {
-						flags.push(Flag::Singleton);
-					} else if class.uncreatable {
-						flags.push(Flag::Uncreatable);
-					}
-
-					flags
-				},
+				flags,
+				replacement,
+				source: class.source.as_ref().map(solvesource),
+				since: None,
 				properties,
 				functions,
 				signals,
 				variants,
-			}),
+				inherited,
+				attached,
+			})),
 		};
 
 		outtypes.insert(mapping.name.clone(), type_);
 	}
 
+	let ambiguous = index.ambiguous.into_inner();
+	if !ambiguous.is_empty() {
+		let names = ambiguous
+			.iter()
+			.map(|(name, modules)| format!("`{name}` ({})", modules.join(", ")))
+			.collect::<Vec<_>>();
+
+		bail!(
+			"ambiguous QML types, list only one of the modules providing each in `imports`: {}",
+			names.join(", ")
+		);
+	}
+
 	for enum_ in typespec.enums {
 		if enum_.module.as_ref().map(|v| v as &str) == Some(module) {
+			let mut flags = Vec::new();
+			let replacement = solvedeprecation(&enum_.deprecated, &mut flags);
+
 			outtypes.insert(enum_.name.clone(), outform::TypeInfo {
 				name: enum_.name,
 				module: enum_.module.unwrap(),
-				details: outform::TypeDetails::Enum(outform::EnumInfo {
+				details: outform::TypeDetails::Enum(Box::new(outform::EnumInfo {
 					description: enum_.description,
 					details: enum_.details,
+					flags,
+					replacement,
+					source: enum_.source.as_ref().map(solvesource),
+					since: None,
 					variants: enum_
 						.varaints
 						.into_iter()
 						.map(|variant| {
+							let mut flags = Vec::new();
+							let replacement = solvedeprecation(&variant.deprecated, &mut flags);
+
 							(variant.name, outform::Variant {
 								details: variant.details,
+								flags,
+								replacement,
+								since: None,
 							})
 						})
 						.collect(),
-				}),
+				})),
+			});
+		}
+	}
+
+	Ok(Resolved {
+		types: outtypes,
+		unresolved,
+		overrides,
+	})
+}
+
+fn solvesource(source: &SourceLocation) -> outform::SourceLocation {
+	outform::SourceLocation {
+		file: source.file.clone(),
+		line: source.line,
+	}
+}
+
+/// Keeps the id made of the name and parameter types given by `solvefunc` for overloaded
+/// functions, e.g. `foo(int,string)`, and sorts overloads by it when members are alphabetical.
+/// Functions that are not overloaded are identified by their name alone.
+///
+/// Overloads with a unique parameter count can also be selected by it, e.g. `foo(2)`, and the
+/// first overload listed can be selected by the name alone.
+fn solveoverloads(functions: &mut [outform::Function], order: MemberOrder) {
+	let mut overloads = HashMap::<String, Vec<usize>>::new();
+
+	for (i, func) in functions.iter().enumerate() {
+		overloads.entry(func.name.clone()).or_default().push(i);
+	}
+
+	for group in overloads.values() {
+		if let [i] = group[..] {
+			functions[i].id = functions[i].name.clone();
+			continue
+		}
+
+		let mut ids = group
+			.iter()
+			.map(|i| functions[*i].id.clone())
+			.collect::<Vec<_>>();
+		ids.sort();
+
+		let arities = group
+			.iter()
+			.map(|i| functions[*i].params.len())
+			.collect::<Vec<_>>();
+
+		for i in group {
+			let func = &mut functions[*i];
+			let arity = func.params.len();
+
+			if arities.iter().filter(|a| **a == arity).count() == 1 {
+				func.arity_id = Some(format!("{}({arity})", func.name));
+			}
+
+			func.overloads = ids.iter().filter(|id| **id != func.id).cloned().collect();
+		}
+	}
+
+	if order == MemberOrder::Alpha {
+		functions.sort_by(|a, b| Ord::cmp(&(&a.name, &a.id), &(&b.name, &b.id)));
+	}
+
+	let mut named = HashSet::new();
+	for func in functions {
+		if !func.overloads.is_empty() && named.insert(func.name.clone()) {
+			func.name_id = Some(func.name.clone());
+		}
+	}
+}
+
+/// Drops overloads with the same parameter types in qml as an earlier one, keeping the nearest
+/// declaration. These cannot be told apart from qml, e.g. const and non-const overloads, or
+/// overloads taking `qint32` and `qint64`, which are both `int`.
+fn mergeoverloads<T>(functions: &mut Vec<T>, func: impl Fn(&T) -> &outform::Function) {
+	let mut ids = HashSet::new();
+	functions.retain(|f| ids.insert(func(f).id.clone()));
+}
+
+/// Superclasses of `class` not visible from QML, whose members are documented on it, nearest first,
+/// and the name of the first superclass that is visible or could not be found.
+fn hidden_superclasses<'a>(index: &Index<'a>, class: &'a Class) -> (Vec<&'a Class>, &'a String) {
+	let mut superclasses = Vec::new();
+	let mut superclass = &class.superclass;
+
+	while index.qml_type(superclass).is_none() {
+		let Some(superctype) = index.class(superclass) else { break };
+
+		superclasses.push(superctype);
+		superclass = &superctype.superclass;
+	}
+
+	(superclasses, superclass)
+}
+
+/// Classes whose members are documented on the page of `class`, nearest first, and the name
+/// of its first superclass visible from QML, see `hidden_superclasses`.
+///
+/// `QML_EXTENDED` members are availible as if they were defined on the class.
+fn declaring_classes<'a>(index: &Index<'a>, class: &'a Class) -> (Vec<&'a Class>, &'a String) {
+	let (superclasses, superclass) = hidden_superclasses(index, class);
+	let mut declaring = vec![class];
+
+	if let Some(extension) = class.extension.as_ref().and_then(|e| index.class(e)) {
+		declaring.push(extension);
+		declaring.extend(hidden_superclasses(index, extension).0);
+	}
+
+	declaring.extend(superclasses);
+	(declaring, superclass)
+}
+
+/// A documented member, as ordered by `collect_members`.
+trait Member {
+	fn name(&self) -> &str;
+	fn index(&self) -> usize;
+	fn group(&self) -> Option<&str>;
+	fn details(&self) -> &Option<String>;
+	fn source(&self) -> &Option<SourceLocation>;
+
+	/// Members with the same key override each other, functions may be overloaded so their
+	/// parameter types are included.
+	fn key(&self) -> String {
+		self.name().to_string()
+	}
+
+	/// description of the member, e.g. "property `foo`"
+	fn describe(&self) -> String;
+
+	/// How this member changes the `base` member it overrides, in ways users can observe.
+	fn mismatches(&self, base: &Self) -> Vec<String>;
+}
+
+macro_rules! impl_member {
+	() => {
+		fn name(&self) -> &str {
+			&self.name
+		}
+
+		fn index(&self) -> usize {
+			self.index
+		}
+
+		fn group(&self) -> Option<&str> {
+			self.group.as_deref()
+		}
+
+		fn details(&self) -> &Option<String> {
+			&self.details
+		}
+
+		fn source(&self) -> &Option<SourceLocation> {
+			&self.source
+		}
+	};
+}
+
+fn param_types(params: &[FnParam]) -> String {
+	params
+		.iter()
+		.map(|p| &p.type_[..])
+		.collect::<Vec<_>>()
+		.join(", ")
+}
+
+impl Member for Property {
+	impl_member!();
+
+	fn describe(&self) -> String {
+		format!("property `{}`", self.name)
+	}
+
+	fn mismatches(&self, base: &Self) -> Vec<String> {
+		let mut mismatches = Vec::new();
+
+		if self.type_ != base.type_ {
+			mismatches.push(format!(
+				"changes type from `{}` to `{}`",
+				base.type_, self.type_
+			));
+		}
+
+		match (base.writable, self.writable) {
+			(true, false) => mismatches.push("makes a writable property readonly".to_string()),
+			(false, true) => mismatches.push("makes a readonly property writable".to_string()),
+			_ => {},
+		}
+
+		mismatches
+	}
+}
+
+impl Member for Function {
+	impl_member!();
+
+	fn key(&self) -> String {
+		format!("{}({})", self.name, param_types(&self.params))
+	}
+
+	fn describe(&self) -> String {
+		format!("function `{}`", self.key())
+	}
+
+	fn mismatches(&self, base: &Self) -> Vec<String> {
+		match self.ret != base.ret {
+			true => vec![format!(
+				"changes return type from `{}` to `{}`",
+				base.ret, self.ret
+			)],
+			false => Vec::new(),
+		}
+	}
+}
+
+impl Member for Signal {
+	impl_member!();
+
+	fn describe(&self) -> String {
+		format!("signal `{}`", self.name)
+	}
+
+	fn mismatches(&self, base: &Self) -> Vec<String> {
+		let (params, baseparams) = (param_types(&self.params), param_types(&base.params));
+
+		match params != baseparams {
+			true => vec![format!(
+				"changes parameters from `({baseparams})` to `({params})`"
+			)],
+			false => Vec::new(),
+		}
+	}
+}
+
+/// Collects the members of `classes`, which are ordered nearest first, sorted by `order`,
+/// along with the class declaring each and its details.
+///
+/// Members of nearer classes override inherited ones with the same key, inheriting their details
+/// if they have none. Each inherited member is compared against the nearest declaration of its
+/// key, and overrides changing it are reported. Source order lists a class's own members before
+/// inherited ones, and grouped order additionally moves each `@group` section to where it first
+/// appears, after ungrouped members.
+fn collect_members<'a, T: Member>(
+	classes: &[&'a Class],
+	members: impl Fn(&'a Class) -> &'a Vec<T>,
+	order: MemberOrder,
+	report: &mut Report,
+) -> Vec<(&'a Class, &'a T, &'a Option<String>)> {
+	// position in `ranked` of the member kept for a key
+	let mut overridden = HashMap::<String, usize>::new();
+	let mut ranked = Vec::new();
+
+	for (rank, class) in classes.iter().enumerate() {
+		for member in members(class) {
+			match overridden.get(&member.key()) {
+				None => {
+					overridden.insert(member.key(), ranked.len());
+					ranked.push((rank, *class, member, member.details()));
+				},
+				Some(position) => {
+					let (_, overriding_class, overriding, details) = &mut ranked[*position];
+					report.check_override(*overriding, overriding_class, member, class);
+
+					if details.is_none() {
+						*details = member.details();
+					}
+				},
+			}
+		}
+	}
+
+	match order {
+		MemberOrder::Alpha => {
+			ranked.sort_by(|(_, _, a, _), (_, _, b, _)| Ord::cmp(a.name(), b.name()))
+		},
+		MemberOrder::Source => ranked.sort_by_key(|(rank, _, member, _)| (*rank, member.index())),
+		MemberOrder::Grouped => {
+			ranked.sort_by_key(|(rank, _, member, _)| (*rank, member.index()));
+
+			let mut groups = Vec::new();
+			for (_, _, member, _) in &ranked {
+				if let Some(group) = member.group() {
+					if !groups.contains(&group) {
+						groups.push(group);
+					}
+				}
+			}
+
+			let position = |group: Option<&str>| match group {
+				None => 0,
+				Some(group) => groups.iter().position(|g| *g == group).unwrap() + 1,
+			};
+
+			ranked.sort_by_key(|(rank, _, member, _)| {
+				(position(member.group()), *rank, member.index())
 			});
+		},
+	}
+
+	ranked
+		.into_iter()
+		.map(|(_, class, member, details)| (class, member, details))
+		.collect()
+}
+
+/// The c++ class a member was inherited from, if not declared by the documented class itself.
+///
+/// `declaring` is ordered as by `declaring_classes`, starting with the documented class.
+fn inherited_from(declaring: &[&Class], from: &Class) -> Option<String> {
+	(!std::ptr::eq(declaring[0], from)).then(|| from.name.clone())
+}
+
+/// Parameter types as written in overload ids and links, e.g. `int,list<string>`.
+///
+/// Types are spelled by their qml names without whitespace, as in the selectors of `@@` links.
+/// Types that could not be resolved are spelled as declared instead, so overloads taking them
+/// still get distinct ids.
+fn signature(declared: &[FnParam], params: &[Parameter]) -> String {
+	fn qmlname(type_: &outform::Type) -> String {
+		match type_.args.is_empty() {
+			true => type_.name.clone(),
+			false => {
+				let args = type_.args.iter().map(qmlname).collect::<Vec<_>>();
+				format!("{}<{}>", type_.name, args.join(","))
+			},
+		}
+	}
+
+	declared
+		.iter()
+		.zip(params)
+		.map(|(declared, param)| match is_resolved(&param.type_) {
+			true => qmlname(&param.type_),
+			false => declared_type(&declared.type_),
+		})
+		.collect::<Vec<_>>()
+		.join(",")
+}
+
+/// Parameter types as declared, c++ or qml, without whitespace, e.g. `int,QList<QString>`.
+///
+/// Unlike overload ids these do not depend on the types that can be resolved, so they identify
+/// overloads across typespec snapshots.
+pub fn declared_signature(params: &[FnParam]) -> String {
+	params
+		.iter()
+		.map(|p| declared_type(&p.type_))
+		.collect::<Vec<_>>()
+		.join(",")
+}
+
+fn declared_type(ctype: &str) -> String {
+	ctype.replace("QML:", "").split_whitespace().collect()
+}
+
+/// Flags deprecated api, returning its replacement.
+fn solvedeprecation(deprecated: &Option<Deprecation>, flags: &mut Vec<Flag>) -> Option<String> {
+	let deprecated = deprecated.as_ref()?;
+	flags.push(Flag::Deprecated);
+	deprecated.replacement.clone()
+}
+
+/// Lists every deprecated type and member, sorted by name.
+pub fn deprecated_api(types: &BTreeMap<String, outform::TypeInfo>) -> Vec<outform::DeprecatedApi> {
+	let mut deprecated = Vec::new();
+
+	let mut push = |type_: &outform::TypeInfo,
+	                member: Option<(&str, &str)>,
+	                flags: &[Flag],
+	                replacement: &Option<String>| {
+		if flags.iter().any(|f| matches!(f, Flag::Deprecated)) {
+			deprecated.push(outform::DeprecatedApi {
+				type_: outform::TypeSource::Local,
+				module: type_.module.clone(),
+				name: type_.name.clone(),
+				mtype: member.map(|(mtype, _)| mtype.to_string()),
+				// functions are passed by id, which only differs from the name for overloads
+				mname: member.map(|(_, mname)| mname.split('(').next().unwrap().to_string()),
+				id: member
+					.filter(|(_, mname)| mname.contains('('))
+					.map(|(_, id)| id.to_string()),
+				replacement: replacement.clone(),
+			});
+		}
+	};
+
+	for type_ in types.values() {
+		match &type_.details {
+			outform::TypeDetails::Class(class) => {
+				push(type_, None, &class.flags, &class.replacement);
+
+				for prop in &class.properties {
+					push(
+						type_,
+						Some(("prop", &prop.name)),
+						&prop.flags,
+						&prop.replacement,
+					);
+				}
+
+				for func in &class.functions {
+					push(
+						type_,
+						Some(("func", &func.id)),
+						&func.flags,
+						&func.replacement,
+					);
+				}
+
+				for signal in &class.signals {
+					push(
+						type_,
+						Some(("signal", &signal.name)),
+						&signal.flags,
+						&signal.replacement,
+					);
+				}
+
+				for (name, variant) in &class.variants {
+					push(
+						type_,
+						Some(("variant", name)),
+						&variant.flags,
+						&variant.replacement,
+					);
+				}
+			},
+			outform::TypeDetails::Enum(enum_) => {
+				push(type_, None, &enum_.flags, &enum_.replacement);
+
+				for (name, variant) in &enum_.variants {
+					push(
+						type_,
+						Some(("variant", name)),
+						&variant.flags,
+						&variant.replacement,
+					);
+				}
+			},
 		}
 	}
 
-	Ok(outtypes)
+	deprecated.sort_by(|a, b| Ord::cmp(&(&a.name, &a.mname), &(&b.name, &b.mname)));
+	deprecated
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::testutil;
+
+	/// Resolves the functions of a type declaring `functions`, given as names and parameter types.
+	fn functions(functions: &[(&str, &[&str])], order: MemberOrder) -> Vec<outform::Function> {
+		let mut thing = testutil::class("Thing", "QQuickItem");
+		thing.functions = functions
+			.iter()
+			.map(|(name, params)| testutil::function(name, params))
+			.collect();
+
+		let typespec = testutil::typespec(vec![thing]);
+		let mut resolved = resolve_types(testutil::MODULE, &[], typespec, order).unwrap();
+		let outform::TypeDetails::Class(class) = resolved.types.remove("Thing").unwrap().details
+		else {
+			unreachable!()
+		};
+
+		class.functions
+	}
+
+	/// Resolves the parameter types of a function taking each of `ctypes`, written like
+	/// `list<var<string, int>>` with `?` for unknown types.
+	fn param_types(ctypes: &[&str]) -> Vec<String> {
+		fn display(type_: &outform::Type) -> String {
+			let name = match type_.type_ {
+				outform::TypeSource::Unknown => "?",
+				_ => &type_.name,
+			};
+
+			match type_.args.is_empty() {
+				true => name.to_string(),
+				false => {
+					let args = type_.args.iter().map(display).collect::<Vec<_>>();
+					format!("{name}<{}>", args.join(", "))
+				},
+			}
+		}
+
+		functions(&[("f", ctypes)], MemberOrder::Source)[0]
+			.params
+			.iter()
+			.map(|p| display(&p.type_))
+			.collect()
+	}
+
+	#[test]
+	fn generic_params() {
+		assert_eq!(
+			param_types(&[
+				"QList<qint32>",
+				"QList<QQuickItem*>",
+				"QList<QList<Thing*>>",
+				"QList<QMap<QString, qint32>>",
+				"QHash<QString, QList<qint32>>",
+				"QMap<QString,QList<QPair<qint32, qint32>>>",
+				"QList<Unknown>",
+			]),
+			[
+				"list<int>",
+				"list<Item>",
+				"list<list<Thing>>",
+				"list<var<string, int>>",
+				"var<string, list<int>>",
+				"var<string, list<var<int, int>>>",
+				"list<?>",
+			]
+		);
+	}
+
+	#[test]
+	fn aliased_and_qml_params() {
+		assert_eq!(
+			param_types(&[
+				"QVariantMap",
+				"QStringList",
+				"QML:list<Item>",
+				"QML:list<list<Thing>>",
+				"QML:int",
+			]),
+			[
+				"var<string, variant>",
+				"list<string>",
+				"list<Item>",
+				"list<list<Thing>>",
+				"int",
+			]
+		);
+	}
+
+	#[test]
+	fn overload_ids() {
+		let functions = functions(
+			&[
+				("f", &["QString", "QList< qint32 >"]),
+				("f", &["Unknown"]),
+				("f", &["QML:list<Item>"]),
+				("f", &[]),
+				("f", &["QList<Unknown>"]),
+				("g", &["qint32"]),
+				("g", &["QML:int"]),
+			],
+			MemberOrder::Alpha,
+		);
+
+		let ids = functions
+			.iter()
+			.map(|f| (&f.id[..], f.arity_id.as_deref(), f.name_id.as_deref()))
+			.collect::<Vec<_>>();
+
+		// ids use qml type names, but overloads with unknown types still get distinct ids,
+		// spelled as declared, and overloads that are the same in qml are merged
+		assert_eq!(ids, [
+			("f()", Some("f(0)"), Some("f")),
+			("f(QList<Unknown>)", None, None),
+			("f(Unknown)", None, None),
+			("f(list<Item>)", None, None),
+			("f(string,list<int>)", Some("f(2)"), None),
+			("g", None, None),
+		]);
+
+		assert_eq!(functions[3].overloads, [
+			"f()",
+			"f(QList<Unknown>)",
+			"f(Unknown)",
+			"f(string,list<int>)"
+		]);
+	}
+
+	#[test]
+	fn override_pairs() {
+		let property = |name, type_, writable| {
+			let mut property = testutil::property(name, type_);
+			property.writable = writable;
+			property
+		};
+
+		let mut thing = testutil::class("Thing", "Middle");
+		thing.properties = vec![
+			property("bar", "qint32", true),
+			property("baz", "QString", true),
+		];
+
+		let mut middle = testutil::class("Middle", "Base");
+		middle.properties = vec![
+			property("foo", "qint32", false),
+			property("baz", "qint32", true),
+		];
+
+		let mut base = testutil::class("Base", "QObject");
+		base.properties = vec![
+			property("foo", "QString", true),
+			property("bar", "qint32", false),
+			property("baz", "QString", true),
+		];
+
+		// superclasses not registered to qml are merged into the type's page
+		let mut typespec = testutil::typespec(vec![thing, middle, base]);
+		typespec
+			.typemap
+			.retain(|t| t.name != "Middle" && t.name != "Base");
+
+		let resolved = resolve_types(testutil::MODULE, &[], typespec, MemberOrder::Source).unwrap();
+
+		let overrides = resolved
+			.overrides
+			.iter()
+			.filter(|o| o.class == "Thing")
+			.map(|o| {
+				(
+					&o.member[..],
+					&o.class_declaring[..],
+					&o.base[..],
+					&o.reason[..],
+				)
+			})
+			.collect::<Vec<_>>();
+
+		// `baz` is compared against its nearest declaration only, not `Middle`'s against `Base`'s
+		assert_eq!(overrides, [
+			(
+				"property `baz`",
+				"Thing",
+				"Middle",
+				"changes type from `qint32` to `QString`"
+			),
+			(
+				"property `foo`",
+				"Middle",
+				"Base",
+				"changes type from `QString` to `qint32`"
+			),
+			(
+				"property `foo`",
+				"Middle",
+				"Base",
+				"makes a writable property readonly"
+			),
+			(
+				"property `bar`",
+				"Thing",
+				"Base",
+				"makes a readonly property writable"
+			),
+		]);
+	}
 }
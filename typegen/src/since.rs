@@ -0,0 +1,209 @@
+use std::collections::BTreeMap;
+
+use crate::{
+	diff::{self, Api, MemberKind},
+	outform::{Function, Since, TypeDetails, TypeInfo},
+	typespec::TypeSpec,
+};
+
+/// Typespecs of past releases, used to find when types and members were added.
+pub struct History {
+	/// `(version, types)` in chronological order
+	snapshots: Vec<(String, Api)>,
+}
+
+impl History {
+	pub fn new(snapshots: &[(String, TypeSpec)]) -> Self {
+		Self {
+			snapshots: snapshots
+				.iter()
+				.map(|(version, typespec)| (version.clone(), diff::collect_types(typespec)))
+				.collect(),
+		}
+	}
+
+	/// Fills in the `since` fields of `types`, which were resolved from the typespec
+	/// `current` was collected from.
+	pub fn annotate(&self, current: &Api, types: &mut BTreeMap<String, TypeInfo>) {
+		for type_ in types.values_mut() {
+			let key = (type_.module.clone(), type_.name.clone());
+			let type_since = self.since(&(), |types| types.get(&key).map(|_| &()));
+
+			let Some(current) = current.get(&key) else { continue };
+
+			let member_since = |kind: MemberKind, name: &str| {
+				let member = (kind, name.to_string());
+				let sigs = current.members.get(&member)?;
+
+				self.since(sigs, |types| {
+					types.get(&key).and_then(|t| t.members.get(&member))
+				})
+			};
+
+			// overloads are identified by their declared parameter types, which unlike their ids
+			// do not depend on the types resolved, see diff::TypeEntry
+			let function_since = |prefix: &str, func: &Function| match func.overloads.is_empty() {
+				true => member_since(MemberKind::Function, &format!("{prefix}{}", func.name)),
+				false => {
+					let id = format!("{prefix}{}({})", func.name, func.signature);
+					let sig = current.overloads.get(&id)?;
+
+					self.since(sig, |types| {
+						types.get(&key).and_then(|t| t.overloads.get(&id))
+					})
+				},
+			};
+
+			match &mut type_.details {
+				TypeDetails::Class(class) => {
+					class.since = type_since;
+
+					for prop in &mut class.properties {
+						prop.since = member_since(MemberKind::Property, &prop.name);
+					}
+
+					for func in &mut class.functions {
+						func.since = function_since("", func);
+					}
+
+					for signal in &mut class.signals {
+						signal.since = member_since(MemberKind::Signal, &signal.name);
+					}
+
+					// see diff::collect_types, attached members are named `Type.member`
+					if let Some(attached) = &mut class.attached {
+						let prefix = format!("{}.", key.1);
+
+						for prop in &mut attached.properties {
+							let name = format!("{prefix}{}", prop.name);
+							prop.since = member_since(MemberKind::Property, &name);
+						}
+
+						for func in &mut attached.functions {
+							func.since = function_since(&prefix, func);
+						}
+
+						for signal in &mut attached.signals {
+							let name = format!("{prefix}{}", signal.name);
+							signal.since = member_since(MemberKind::Signal, &name);
+						}
+					}
+
+					// see resolver, class variants come from its `Enum` enum
+					for (name, variant) in &mut class.variants {
+						variant.since = member_since(MemberKind::Variant, &format!("Enum.{name}"));
+					}
+				},
+				TypeDetails::Enum(enum_) => {
+					enum_.since = type_since;
+
+					for (name, variant) in &mut enum_.variants {
+						variant.since = member_since(MemberKind::Variant, name);
+					}
+				},
+			}
+		}
+	}
+
+	/// Finds the release an entity was added in, and the release its current signature
+	/// was introduced in if different, or whether it changed after the latest release.
+	///
+	/// Only the most recent uninterrupted run of releases containing the entity is considered,
+	/// so removed and re-added entities count from when they were re-added.
+	fn since<'a, T: PartialEq + 'a>(
+		&'a self,
+		current: &T,
+		get: impl Fn(&'a Api) -> Option<&'a T>,
+	) -> Option<Since> {
+		let mut since = None;
+		let mut same_since = None;
+		let mut same = true;
+
+		for (version, types) in self.snapshots.iter().rev() {
+			let Some(old) = get(types) else { break };
+			since = Some(version);
+			same = same && old == current;
+
+			if same {
+				same_since = Some(version);
+			}
+		}
+
+		let version = since?;
+
+		Some(Since {
+			version: version.clone(),
+			changed: same_since.filter(|v| *v != version).cloned(),
+			// no release has the current signature
+			unreleased_change: same_since.is_none(),
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{parse::MemberOrder, resolver, testutil};
+
+	/// A typespec declaring `Thing` with a property `foo` of `foo_type` and functions
+	/// taking each of `functions`.
+	fn typespec(foo_type: &str, functions: &[(&str, &[&str])]) -> TypeSpec {
+		let mut thing = testutil::class("Thing", "QQuickItem");
+		thing.properties = vec![testutil::property("foo", foo_type)];
+		thing.functions = functions
+			.iter()
+			.map(|(name, params)| testutil::function(name, params))
+			.collect();
+
+		testutil::typespec(vec![thing])
+	}
+
+	#[test]
+	fn member_since() {
+		let history = History::new(&[
+			("0.1".to_string(), typespec("qint32", &[("f", &["qint32"])])),
+			(
+				"0.2".to_string(),
+				typespec("QString", &[("f", &["qint32"]), ("f", &["QString"])]),
+			),
+		]);
+
+		let current = typespec("QString", &[
+			("f", &["qint32"]),
+			("f", &["QString"]),
+			("g", &[]),
+		]);
+		let api = diff::collect_types(&current);
+		let mut types =
+			resolver::resolve_types(testutil::MODULE, &[], current, MemberOrder::Source)
+				.unwrap()
+				.types;
+		history.annotate(&api, &mut types);
+
+		let TypeDetails::Class(thing) = &types["Thing"].details else { unreachable!() };
+		let since = |since: &Option<Since>| {
+			since
+				.as_ref()
+				.map(|s| (s.version.clone(), s.changed.clone()))
+		};
+
+		assert_eq!(since(&thing.since), Some(("0.1".to_string(), None)));
+		assert_eq!(
+			since(&thing.properties[0].since),
+			Some(("0.1".to_string(), Some("0.2".to_string())))
+		);
+
+		// overloads are matched by their declared parameter types
+		let functions = thing
+			.functions
+			.iter()
+			.map(|f| (&f.id[..], since(&f.since)))
+			.collect::<Vec<_>>();
+
+		assert_eq!(functions, [
+			("f(int)", Some(("0.1".to_string(), None))),
+			("f(string)", Some(("0.2".to_string(), None))),
+			("g", None),
+		]);
+	}
+}
@@ -0,0 +1,109 @@
+//! Typespec fixtures shared by the tests of each pass.
+
+use crate::typespec::{Class, FnParam, Function, Property, QmlTypeMapping, TypeSpec};
+
+/// Module every fixture class is declared in.
+pub const MODULE: &str = "Quickshell";
+
+/// A typespec declaring `classes`, each registered under its c++ name, along with a few builtin
+/// and QtQuick types.
+pub fn typespec(classes: Vec<Class>) -> TypeSpec {
+	let builtins = [
+		("int", "qint32", None),
+		("string", "QString", None),
+		("variant", "QVariant", None),
+		("list", "QList", None),
+		("Item", "QQuickItem", Some("qml.QtQuick")),
+	];
+
+	let mut typemap = builtins
+		.into_iter()
+		.map(|(name, cname, module)| QmlTypeMapping {
+			name: name.to_string(),
+			cname: cname.to_string(),
+			module: module.map(str::to_string),
+			anonymous: false,
+		})
+		.collect::<Vec<_>>();
+
+	typemap.extend(classes.iter().map(|class| QmlTypeMapping {
+		name: class.name.clone(),
+		cname: class.name.clone(),
+		module: Some(MODULE.to_string()),
+		anonymous: false,
+	}));
+
+	TypeSpec {
+		typemap,
+		classes,
+		gadgets: Vec::new(),
+		enums: Vec::new(),
+	}
+}
+
+/// A class without members deriving from `superclass`.
+pub fn class(name: &str, superclass: &str) -> Class {
+	Class {
+		name: name.to_string(),
+		module: MODULE.to_string(),
+		description: None,
+		details: None,
+		superclass: superclass.to_string(),
+		singleton: false,
+		uncreatable: false,
+		source: None,
+		deprecated: None,
+		attached: None,
+		extension: None,
+		properties: Vec::new(),
+		functions: Vec::new(),
+		signals: Vec::new(),
+		enums: Vec::new(),
+	}
+}
+
+/// A writable property of the c++ type `type_`.
+pub fn property(name: &str, type_: &str) -> Property {
+	Property {
+		type_: type_.to_string(),
+		name: name.to_string(),
+		details: None,
+		readable: true,
+		writable: true,
+		default: false,
+		notify: None,
+		nullable: false,
+		index: 0,
+		group: None,
+		source: None,
+		deprecated: None,
+	}
+}
+
+/// A void function taking parameters of the c++ types `params`.
+pub fn function(name: &str, params: &[&str]) -> Function {
+	Function {
+		ret: "void".to_string(),
+		name: name.to_string(),
+		details: None,
+		params: self::params(params),
+		nullable: false,
+		index: 0,
+		group: None,
+		source: None,
+		deprecated: None,
+	}
+}
+
+fn params(types: &[&str]) -> Vec<FnParam> {
+	types
+		.iter()
+		.enumerate()
+		.map(|(i, type_)| FnParam {
+			type_: type_.to_string(),
+			name: format!("p{i}"),
+			default: None,
+			nullable: false,
+		})
+		.collect()
+}
@@ -0,0 +1,279 @@
+use anyhow::bail;
+
+use crate::location::Source;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+	Ident,
+	Punct,
+	Literal,
+	/// `///` comment
+	DocComment,
+	/// any other comment or a preprocessor line
+	Comment,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Token<'a> {
+	pub kind: TokenKind,
+	pub text: &'a str,
+	/// byte offset of the token in the source text
+	pub start: usize,
+}
+
+impl Token<'_> {
+	pub fn end(&self) -> usize {
+		self.start + self.text.len()
+	}
+
+	pub fn is(&self, kind: TokenKind, text: &str) -> bool {
+		self.kind == kind && self.text == text
+	}
+
+	pub fn is_punct(&self, text: &str) -> bool {
+		self.is(TokenKind::Punct, text)
+	}
+
+	pub fn is_ident(&self, text: &str) -> bool {
+		self.is(TokenKind::Ident, text)
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lang {
+	Cpp,
+	Qml,
+}
+
+pub fn tokenize<'a>(src: Source<'a>, lang: Lang) -> anyhow::Result<Vec<Token<'a>>> {
+	let text = src.text;
+	let bytes = text.as_bytes();
+	let mut tokens = Vec::new();
+	let mut i = 0;
+	// only whitespace seen since the last newline
+	let mut line_start = true;
+
+	while i < bytes.len() {
+		let c = bytes[i];
+		let start = i;
+
+		if c == b'\n' {
+			line_start = true;
+			i += 1;
+			continue
+		}
+
+		if c.is_ascii_whitespace() {
+			i += 1;
+			continue
+		}
+
+		// js regex literals may contain quotes, e.g. `/"/.test(s)`
+		let regex = match lang == Lang::Qml && c == b'/' && regex_allowed(&tokens) {
+			true => regex_literal_len(&text[i..]),
+			false => None,
+		};
+
+		let kind = if let Some(len) = regex {
+			i += len;
+			TokenKind::Literal
+		} else if lang == Lang::Cpp && c == b'#' && line_start {
+			// preprocessor lines, including `\` continuations
+			while i < bytes.len() && bytes[i] != b'\n' {
+				if bytes[i] == b'\\' && bytes.get(i + 1) == Some(&b'\n') {
+					i += 1;
+				}
+
+				i += 1;
+			}
+
+			TokenKind::Comment
+		} else if text[i..].starts_with("//@") && lang == Lang::Cpp {
+			// `//@` marks documentation-only code that should be parsed as if uncommented
+			i += 3;
+			line_start = false;
+			continue
+		} else if text[i..].starts_with("//") {
+			let doc = text[i..].starts_with("///") && !text[i..].starts_with("////");
+
+			while i < bytes.len() && bytes[i] != b'\n' {
+				i += 1;
+			}
+
+			match doc {
+				true => TokenKind::DocComment,
+				false => TokenKind::Comment,
+			}
+		} else if text[i..].starts_with("/*") {
+			match text[i + 2..].find("*/") {
+				Some(end) => i += end + 4,
+				None => bail!("unterminated block comment at {}", src.at(start)),
+			}
+
+			TokenKind::Comment
+		} else if c == b'"' || c == b'\'' || (c == b'`' && lang == Lang::Qml) {
+			i += 1;
+
+			loop {
+				match bytes.get(i) {
+					None => bail!("unterminated string literal at {}", src.at(start)),
+					Some(b'\\') => i += 2,
+					Some(&q) if q == c => {
+						i += 1;
+						break
+					},
+					Some(_) => i += 1,
+				}
+			}
+
+			TokenKind::Literal
+		} else if c.is_ascii_digit() {
+			// also covers digit separators and suffixes, e.g. `1'000ull`
+			while i < bytes.len()
+				&& (bytes[i].is_ascii_alphanumeric() || matches!(bytes[i], b'.' | b'\'' | b'_'))
+			{
+				i += 1;
+			}
+
+			TokenKind::Literal
+		} else if c.is_ascii_alphabetic() || c == b'_' || c >= 0x80 {
+			while i < bytes.len()
+				&& (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_' || bytes[i] >= 0x80)
+			{
+				i += 1;
+			}
+
+			// raw string literals, e.g. `R"(...)"` or `u8R"x(...)x"`
+			if lang == Lang::Cpp && text[start..i].ends_with('R') && bytes.get(i) == Some(&b'"') {
+				let Some(open) = text[i..].find('(') else {
+					bail!("malformed raw string literal at {}", src.at(start));
+				};
+
+				let delim = format!("){}\"", &text[i + 1..i + open]);
+
+				match text[i + open..].find(&delim) {
+					Some(end) => i += open + end + delim.len(),
+					None => bail!("unterminated raw string literal at {}", src.at(start)),
+				}
+
+				TokenKind::Literal
+			} else {
+				TokenKind::Ident
+			}
+		} else if text[i..].starts_with("::") {
+			i += 2;
+			TokenKind::Punct
+		} else {
+			i += text[i..].chars().next().unwrap().len_utf8();
+			TokenKind::Punct
+		};
+
+		line_start = false;
+
+		tokens.push(Token {
+			kind,
+			text: &text[start..i],
+			start,
+		});
+	}
+
+	Ok(tokens)
+}
+
+/// Whether a `/` following `tokens` starts a js regex literal rather than a division,
+/// which is the case where an operand is expected.
+fn regex_allowed(tokens: &[Token]) -> bool {
+	const KEYWORDS: &[&str] = &[
+		"return",
+		"typeof",
+		"instanceof",
+		"in",
+		"of",
+		"new",
+		"delete",
+		"void",
+		"throw",
+		"case",
+		"do",
+		"else",
+		"yield",
+		"await",
+	];
+
+	let previous = tokens
+		.iter()
+		.rev()
+		.find(|t| !matches!(t.kind, TokenKind::Comment | TokenKind::DocComment));
+
+	match previous {
+		None => true,
+		Some(token) => match token.kind {
+			TokenKind::Punct => !matches!(token.text, ")" | "]" | "}"),
+			TokenKind::Ident => KEYWORDS.contains(&token.text),
+			_ => false,
+		},
+	}
+}
+
+/// Length of the js regex literal at the start of `text`, including its flags, if it is one.
+fn regex_literal_len(text: &str) -> Option<usize> {
+	let bytes = text.as_bytes();
+
+	// `//` and `/*` start comments
+	if matches!(bytes.get(1), None | Some(b'/' | b'*')) {
+		return None
+	}
+
+	let mut i = 1;
+	let mut class = false;
+
+	loop {
+		match bytes.get(i)? {
+			b'\\' => i += 1,
+			b'\n' => return None,
+			b'[' => class = true,
+			b']' => class = false,
+			b'/' if !class => break,
+			_ => {},
+		}
+
+		i += 1;
+	}
+
+	i += 1;
+	while i < bytes.len() && bytes[i].is_ascii_alphabetic() {
+		i += 1;
+	}
+
+	Some(i)
+}
+
+/// Finds the index of the token closing the bracket opened at `open`.
+pub fn matching_close(src: Source, tokens: &[Token], open: usize) -> anyhow::Result<usize> {
+	let mut depth = 0usize;
+
+	for (i, token) in tokens.iter().enumerate().skip(open) {
+		if token.kind != TokenKind::Punct {
+			continue
+		}
+
+		match token.text {
+			"(" | "[" | "{" => depth += 1,
+			")" | "]" | "}" => {
+				let Some(remaining) = depth.checked_sub(1) else {
+					bail!("unmatched `{}` at {}", token.text, src.at(token.start));
+				};
+
+				depth = remaining;
+
+				if depth == 0 {
+					return Ok(i)
+				}
+			},
+			_ => {},
+		}
+	}
+
+	let open = &tokens[open];
+	bail!("unterminated `{}` at {}", open.text, src.at(open.start))
+}
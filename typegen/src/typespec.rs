@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Default)]
 pub struct TypeSpec {
 	pub typemap: Vec<QmlTypeMapping>,
 	pub classes: Vec<Class>,
@@ -8,22 +8,14 @@ pub struct TypeSpec {
 	pub enums: Vec<Enum>,
 }
 
-impl Default for TypeSpec {
-	fn default() -> Self {
-		Self {
-			typemap: Vec::new(),
-			classes: Vec::new(),
-			gadgets: Vec::new(),
-			enums: Vec::new(),
-		}
-	}
-}
-
 #[derive(Debug, Serialize, Deserialize)]
 pub struct QmlTypeMapping {
 	pub name: String,
 	pub cname: String,
 	pub module: Option<String>,
+	/// registered with `QML_ANONYMOUS`, so `name` is the c++ name and there is no page for it
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub anonymous: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -35,6 +27,16 @@ pub struct Class {
 	pub superclass: String,
 	pub singleton: bool,
 	pub uncreatable: bool,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub source: Option<SourceLocation>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub deprecated: Option<Deprecation>,
+	/// c++ name of the class providing the attached object
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub attached: Option<String>,
+	/// c++ name of the class extending this one, whose members are merged into it
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub extension: Option<String>,
 	pub properties: Vec<Property>,
 	pub functions: Vec<Function>,
 	pub signals: Vec<Signal>,
@@ -56,6 +58,22 @@ pub struct Property {
 	pub readable: bool,
 	pub writable: bool,
 	pub default: bool,
+	/// signal emitted when the property changes, which is not listed in `signals`
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub notify: Option<String>,
+	/// whether the value may be null
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub nullable: bool,
+	/// position among all members of the class in declaration order
+	#[serde(default)]
+	pub index: usize,
+	/// section from a `@group` comment
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub group: Option<String>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub source: Option<SourceLocation>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub deprecated: Option<Deprecation>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -64,6 +82,19 @@ pub struct Function {
 	pub name: String,
 	pub details: Option<String>,
 	pub params: Vec<FnParam>,
+	/// whether the return value may be null
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub nullable: bool,
+	/// position among all members of the class in declaration order
+	#[serde(default)]
+	pub index: usize,
+	/// section from a `@group` comment
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub group: Option<String>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub source: Option<SourceLocation>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub deprecated: Option<Deprecation>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -71,6 +102,16 @@ pub struct Signal {
 	pub name: String,
 	pub details: Option<String>,
 	pub params: Vec<FnParam>,
+	/// position among all members of the class in declaration order
+	#[serde(default)]
+	pub index: usize,
+	/// section from a `@group` comment
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub group: Option<String>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub source: Option<SourceLocation>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub deprecated: Option<Deprecation>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -78,6 +119,11 @@ pub struct FnParam {
 	#[serde(rename = "type")]
 	pub type_: String,
 	pub name: String,
+	/// default value as written in the source
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub default: Option<String>,
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub nullable: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -88,10 +134,32 @@ pub struct Enum {
 	pub description: Option<String>,
 	pub details: Option<String>,
 	pub varaints: Vec<Variant>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub source: Option<SourceLocation>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub deprecated: Option<Deprecation>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Variant {
 	pub name: String,
 	pub details: Option<String>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub source: Option<SourceLocation>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub deprecated: Option<Deprecation>,
+}
+
+/// Where an entity was declared, relative to the source root.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceLocation {
+	pub file: String,
+	pub line: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Deprecation {
+	/// type link to the api replacing the deprecated one
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub replacement: Option<String>,
 }